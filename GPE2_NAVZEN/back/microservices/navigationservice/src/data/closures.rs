@@ -0,0 +1,215 @@
+// Fermetures temporaires de zones (nettoyage, travaux...), enregistrées à chaud par l'API
+// et sauvegardées dans un fichier JSON pour survivre aux redémarrages.
+
+use std::fs;
+use std::time::{SystemTime, UNIX_EPOCH};
+use crate::data::loader::{Map, SurfaceType};
+use log::{error, info, warn};
+use serde::{Deserialize, Serialize};
+
+/// Zone fermée, en cases de la carte
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ClosureArea {
+    Rectangle { x_min: usize, y_min: usize, x_max: usize, y_max: usize }, // Bornes incluses
+    Polygon { points: Vec<(usize, usize)> },                              // Sommets, dans l'ordre du contour
+    Room { room: usize },
+    Outdoors, // Toutes les cases extérieures
+}
+
+/// Fermeture enregistrée : zone, motif et date d'expiration éventuelle (secondes Unix)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Closure {
+    pub id: u64,
+    pub area: ClosureArea,
+    pub reason: Option<String>,
+    pub expires_at: Option<u64>,
+    pub penalty: Option<f32>, // Multiplicateur de coût ; absent, la zone est infranchissable
+}
+
+/// Instant présent en secondes Unix
+pub fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs())
+}
+
+impl ClosureArea {
+    /// Cases couvertes par la zone (vide si elle sort de la carte ou ne désigne aucune case)
+    pub fn cells(&self, map: &Map) -> Vec<(usize, usize)> {
+        match self {
+            ClosureArea::Rectangle { x_min, y_min, x_max, y_max } => (*y_min..=(*y_max).min(map.height - 1))
+                .flat_map(|y| (*x_min..=(*x_max).min(map.width - 1)).map(move |x| (x, y)))
+                .collect(),
+            ClosureArea::Polygon { points } if points.len() >= 3 => {
+                let x_max = points.iter().map(|p| p.0).max().unwrap_or(0).min(map.width - 1);
+                let y_max = points.iter().map(|p| p.1).max().unwrap_or(0).min(map.height - 1);
+                let x_min = points.iter().map(|p| p.0).min().unwrap_or(0);
+                let y_min = points.iter().map(|p| p.1).min().unwrap_or(0);
+                (y_min..=y_max)
+                    .flat_map(|y| (x_min..=x_max).map(move |x| (x, y)))
+                    .filter(|&cell| points.contains(&cell) || inside_polygon(points, cell))
+                    .collect()
+            }
+            ClosureArea::Polygon { .. } => vec![],
+            ClosureArea::Room { room } => map.room_cells(*room),
+            ClosureArea::Outdoors => (0..map.width * map.height)
+                .map(|i| map.position(i))
+                .filter(|&(x, y)| map.get_surface(x, y) == SurfaceType::Exterieur)
+                .collect(),
+        }
+    }
+}
+
+// Test pair-impair du centre de la case sur le contour reliant les centres des sommets
+fn inside_polygon(points: &[(usize, usize)], (x, y): (usize, usize)) -> bool {
+    let (px, py) = (x as f32, y as f32);
+    let mut inside = false;
+    let mut previous = points[points.len() - 1];
+    for &current in points {
+        let (x1, y1) = (previous.0 as f32, previous.1 as f32);
+        let (x2, y2) = (current.0 as f32, current.1 as f32);
+        if (y1 > py) != (y2 > py) && px < x1 + (py - y1) * (x2 - x1) / (y2 - y1) {
+            inside = !inside;
+        }
+        previous = current;
+    }
+    inside
+}
+
+// Contenu du fichier : fermetures et prochain identifiant, sauvegardé pour qu'un identifiant
+// ne resserve jamais (les premiers fichiers ne contenaient que la liste)
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum StoredClosures {
+    Current { next_id: u64, closures: Vec<Closure> },
+    Legacy(Vec<Closure>),
+}
+
+#[derive(Serialize)]
+struct StoredClosuresRef<'a> {
+    next_id: u64,
+    closures: &'a [Closure],
+}
+
+/// Fermetures en vigueur, synchronisées avec le fichier `path` (le même registre sert aux
+/// zones dangereuses déclarées pendant une évacuation, dans leur propre fichier)
+pub struct ClosureStore {
+    path: String,
+    kind: &'static str, // Nom d'une entrée dans les logs ("fermeture", "zone dangereuse")
+    closures: Vec<Closure>,
+    next_id: u64, // Croissant, même après suppression ou expiration des dernières entrées
+}
+
+impl ClosureStore {
+    /// Charge les fermetures sauvegardées (aucune si le fichier n'existe pas encore)
+    pub fn load(path: &str, kind: &'static str) -> Self {
+        let stored = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                error!("❌ Fichier `{}` invalide ({}s ignorées) : {}", path, kind, e);
+                StoredClosures::Legacy(vec![])
+            }),
+            Err(_) => StoredClosures::Legacy(vec![]),
+        };
+        let (next_id, closures) = match stored {
+            StoredClosures::Current { next_id, closures } => (next_id, closures),
+            StoredClosures::Legacy(closures) => (1, closures),
+        };
+        let next_id = closures.iter().map(|c| c.id + 1).fold(next_id.max(1), u64::max);
+
+        let mut store = Self { path: path.to_string(), kind, closures, next_id };
+        store.purge_expired(now());
+        info!("🚧 {} {}(s) en vigueur chargée(s) depuis `{}`", store.closures.len(), kind, path);
+        store
+    }
+
+    pub fn list(&self) -> &[Closure] {
+        &self.closures
+    }
+
+    /// Enregistre une nouvelle fermeture et lui attribue un identifiant
+    pub fn add(&mut self, area: ClosureArea, reason: Option<String>, expires_at: Option<u64>, penalty: Option<f32>) -> Closure {
+        let id = self.next_id;
+        self.next_id += 1;
+        let closure = Closure { id, area, reason, expires_at, penalty };
+        self.closures.push(closure.clone());
+        self.save();
+        closure
+    }
+
+    /// Supprime une fermeture ; false si elle n'existe pas
+    pub fn remove(&mut self, id: u64) -> bool {
+        let before = self.closures.len();
+        self.closures.retain(|c| c.id != id);
+        let removed = self.closures.len() != before;
+        if removed {
+            self.save();
+        }
+        removed
+    }
+
+    /// Retire les fermetures expirées à l'instant `now` ; true si la liste a changé
+    pub fn purge_expired(&mut self, now: u64) -> bool {
+        let before = self.closures.len();
+        self.closures.retain(|c| !matches!(c.expires_at, Some(expiry) if expiry <= now));
+        let purged = self.closures.len() != before;
+        if purged {
+            info!("⌛ {} {}(s) expirée(s) retirée(s)", before - self.closures.len(), self.kind);
+            self.save();
+        }
+        purged
+    }
+
+    /// Prochaine expiration parmi les fermetures en vigueur
+    pub fn next_expiry(&self) -> Option<u64> {
+        self.closures.iter().filter_map(|c| c.expires_at).min()
+    }
+
+    fn save(&self) {
+        let stored = StoredClosuresRef { next_id: self.next_id, closures: &self.closures };
+        let result = serde_json::to_string_pretty(&stored)
+            .map_err(|e| e.to_string())
+            .and_then(|content| fs::write(&self.path, content).map_err(|e| e.to_string()));
+        if let Err(e) = result {
+            warn!("⚠️ Impossible de sauvegarder les {}s dans `{}` : {}", self.kind, self.path, e);
+        }
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn store_path(name: &str) -> String {
+        let path = std::env::temp_dir().join(format!("navigationservice-{}-{}.json", name, std::process::id()));
+        let _ = fs::remove_file(&path);
+        path.to_string_lossy().into_owned()
+    }
+
+    #[test]
+    fn ids_are_never_reused() {
+        let path = store_path("ids");
+        let mut store = ClosureStore::load(&path, "fermeture");
+        let first = store.add(ClosureArea::Outdoors, None, None, None).id;
+        let second = store.add(ClosureArea::Outdoors, None, None, None).id;
+        assert!(store.remove(second));
+
+        // Le dernier identifiant supprimé ne resert pas, ni après un rechargement
+        assert_eq!(store.add(ClosureArea::Outdoors, None, None, None).id, second + 1);
+        let mut reloaded = ClosureStore::load(&path, "fermeture");
+        assert!(reloaded.remove(second + 1));
+        assert_eq!(reloaded.add(ClosureArea::Outdoors, None, None, None).id, second + 2);
+        assert_eq!(reloaded.list().iter().map(|c| c.id).collect::<Vec<_>>(), vec![first, second + 2]);
+        let _ = fs::remove_file(&path);
+    }
+
+    #[test]
+    fn legacy_list_files_still_load() {
+        let path = store_path("legacy");
+        fs::write(&path, r#"[{"id": 7, "area": {"type": "outdoors"}, "reason": null, "expires_at": null, "penalty": null}]"#).unwrap();
+
+        let mut store = ClosureStore::load(&path, "fermeture");
+        assert_eq!(store.list().len(), 1);
+        assert_eq!(store.add(ClosureArea::Outdoors, None, None, None).id, 8);
+        let _ = fs::remove_file(&path);
+    }
+}
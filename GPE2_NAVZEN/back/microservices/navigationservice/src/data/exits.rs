@@ -0,0 +1,55 @@
+// Sorties de secours désignées (portes donnant sur l'extérieur, escaliers protégés...).
+//
+// En évacuation, toute case extérieure est un lieu sûr ; ces zones s'y ajoutent pour les
+// sorties que la carte ne montre pas comme débouchant sur l'extérieur.
+
+use std::fs;
+use crate::data::closures::ClosureArea;
+use crate::data::loader::Map;
+use log::{error, info, warn};
+use serde::Deserialize;
+
+// Sortie telle qu'écrite dans le fichier : `{ "name": "Sortie nord", "area": {...} }`
+#[derive(Deserialize)]
+struct ExitEntry {
+    name: Option<String>,
+    area: ClosureArea,
+}
+
+/// Sortie de secours et les cases qui la composent
+pub struct Exit {
+    pub name: String,
+    pub cells: Vec<(usize, usize)>,
+}
+
+/// Charge les sorties désignées depuis un fichier JSON (aucune si le fichier est absent) ;
+/// les sorties qui ne couvrent aucune case sont ignorées
+pub fn load_exits(path: &str, map: &Map) -> Vec<Exit> {
+    let entries: Vec<ExitEntry> = match fs::read_to_string(path) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            error!("❌ Fichier de sorties `{}` invalide : {}", path, e);
+            vec![]
+        }),
+        Err(e) => {
+            warn!("⚠️ Impossible de lire `{}` ({}), seules les cases extérieures serviront de sorties", path, e);
+            vec![]
+        }
+    };
+
+    let exits: Vec<Exit> = entries
+        .into_iter()
+        .enumerate()
+        .filter_map(|(position, entry)| {
+            let name = entry.name.unwrap_or_else(|| format!("sortie n°{}", position + 1));
+            let cells = entry.area.cells(map);
+            if cells.is_empty() {
+                warn!("⚠️ Sortie `{}` ignorée : sa zone ne couvre aucune case", name);
+                return None;
+            }
+            Some(Exit { name, cells })
+        })
+        .collect();
+
+    info!("🚪 Sorties de secours chargées : {:?}", exits.iter().map(|exit| exit.name.as_str()).collect::<Vec<_>>());
+    exits
+}
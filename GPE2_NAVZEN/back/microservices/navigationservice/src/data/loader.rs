@@ -107,6 +107,21 @@ impl Map {
         self.grid[y][x]
     }
 
+    /// Indique si une position (x, y) est dans les limites de la carte
+    pub fn contains(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height
+    }
+
+    /// Convertit une position (x, y) en indice dans la grille aplatie (ligne par ligne)
+    pub fn index(&self, x: usize, y: usize) -> usize {
+        y * self.width + x
+    }
+
+    /// Convertit un indice de la grille aplatie en position (x, y)
+    pub fn position(&self, index: usize) -> (usize, usize) {
+        (index % self.width, index / self.width)
+    }
+
     /// Retourne le numéro de ligne du fichier source correspondant à une position (x, y)
     pub fn get_line_number(&self, x: usize, y: usize) -> usize {
         *self.line_map.get(&(x, y)).unwrap_or(&0) // Retourne 0 si absent
@@ -143,6 +158,28 @@ impl Map {
 
         count >= max_range // Si la case a au moins `max_range` cases couloir autour, c'est un large couloir
     }
+
+    /// Retourne toutes les cases d'une salle (vide si la salle n'existe pas)
+    pub fn room_cells(&self, room: usize) -> Vec<(usize, usize)> {
+        (0..self.height)
+            .flat_map(|y| (0..self.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| self.grid[y][x] == SurfaceType::Room(room))
+            .collect()
+    }
+
+    /// Case de la salle la plus proche de son centre, pour la désigner par un point unique
+    pub fn room_center(&self, room: usize) -> Option<(usize, usize)> {
+        let cells = self.room_cells(room);
+        let count = cells.len() as f32;
+        let cx = cells.iter().map(|&(x, _)| x as f32).sum::<f32>() / count;
+        let cy = cells.iter().map(|&(_, y)| y as f32).sum::<f32>() / count;
+
+        cells.into_iter().min_by(|a, b| {
+            let da = (a.0 as f32 - cx).powi(2) + (a.1 as f32 - cy).powi(2);
+            let db = (b.0 as f32 - cx).powi(2) + (b.1 as f32 - cy).powi(2);
+            da.total_cmp(&db)
+        })
+    }
 }
 
 // // src/data/loader.rs
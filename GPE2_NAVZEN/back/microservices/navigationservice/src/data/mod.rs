@@ -1,3 +1,6 @@
 
+pub mod closures;
+pub mod exits;
 pub mod loader;
+pub mod schedules;
 pub mod surface;
\ No newline at end of file
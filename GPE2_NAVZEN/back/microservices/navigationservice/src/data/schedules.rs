@@ -0,0 +1,111 @@
+// Horaires d'ouverture des salles, portes et zones (passages extérieurs...).
+//
+// Chaque horaire associe une zone à des plages d'ouverture par jour de la semaine, en
+// heure locale. Un jour absent est un jour de fermeture ; une plage dont la fin précède
+// le début (`22:00-02:00`) se prolonge après minuit. Une case couverte par plusieurs
+// horaires n'est ouverte que si tous le sont.
+
+use std::collections::BTreeMap;
+use std::fs;
+use crate::data::closures::ClosureArea;
+use crate::data::loader::Map;
+use crate::utils::clock::{minute_of_day, parse_time_of_day, weekday};
+use log::{error, info, warn};
+use serde::Deserialize;
+
+const DAYS: [&str; 7] = ["lundi", "mardi", "mercredi", "jeudi", "vendredi", "samedi", "dimanche"];
+
+// Horaire tel qu'écrit dans le fichier : `{ "area": {...}, "hours": { "lundi": ["08:00-19:00"] } }`
+#[derive(Deserialize)]
+struct ScheduleEntry {
+    name: Option<String>,
+    area: ClosureArea,
+    hours: BTreeMap<String, Vec<String>>,
+}
+
+struct Schedule {
+    name: String,
+    days: [Vec<(u32, u32)>; 7], // Plages d'ouverture de chaque jour, en minutes depuis minuit
+}
+
+impl Schedule {
+    fn is_open(&self, instant: u64) -> bool {
+        let minute = minute_of_day(instant);
+        let today = weekday(instant);
+        let yesterday = (today + 6) % 7;
+
+        self.days[today].iter().any(|&(start, end)| {
+            if start <= end { start <= minute && minute < end } else { minute >= start }
+        }) || self.days[yesterday].iter().any(|&(start, end)| start > end && minute < end)
+    }
+}
+
+// Plages d'ouverture par jour ; None si un jour ou une plage est mal écrit
+fn parse_days(hours: &BTreeMap<String, Vec<String>>) -> Option<[Vec<(u32, u32)>; 7]> {
+    let mut days: [Vec<(u32, u32)>; 7] = Default::default();
+    for (day, ranges) in hours {
+        let index = DAYS.iter().position(|name| name.eq_ignore_ascii_case(day.trim()))?;
+        for range in ranges {
+            let (start, end) = range.split_once('-')?;
+            days[index].push((parse_time_of_day(start)?, parse_time_of_day(end)?));
+        }
+    }
+    Some(days)
+}
+
+/// Calendrier des zones à horaires, indexé par case de la carte
+pub struct Calendar {
+    schedules: Vec<Schedule>,
+    covering: Vec<Vec<usize>>, // Pour chaque case : horaires qui la concernent
+}
+
+impl Calendar {
+    /// Charge les horaires depuis un fichier JSON (aucun si le fichier est absent) ;
+    /// les horaires mal écrits sont ignorés
+    pub fn load(path: &str, map: &Map) -> Self {
+        let entries: Vec<ScheduleEntry> = match fs::read_to_string(path) {
+            Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+                error!("❌ Fichier d'horaires `{}` invalide : {}", path, e);
+                vec![]
+            }),
+            Err(e) => {
+                warn!("⚠️ Impossible de lire `{}` ({}), aucune zone à horaires", path, e);
+                vec![]
+            }
+        };
+
+        let mut calendar = Self { schedules: vec![], covering: vec![vec![]; map.width * map.height] };
+        for (position, entry) in entries.into_iter().enumerate() {
+            let name = entry.name.unwrap_or_else(|| format!("horaire n°{}", position + 1));
+            let Some(days) = parse_days(&entry.hours) else {
+                error!("⚠️ Horaire `{}` ignoré : jours attendus {:?}, plages au format HH:MM-HH:MM", name, DAYS);
+                continue;
+            };
+
+            let cells = entry.area.cells(map);
+            if cells.is_empty() {
+                warn!("⚠️ Horaire `{}` ignoré : sa zone ne couvre aucune case", name);
+                continue;
+            }
+            for (x, y) in cells {
+                calendar.covering[map.index(x, y)].push(calendar.schedules.len());
+            }
+            calendar.schedules.push(Schedule { name, days });
+        }
+
+        info!(
+            "🕒 Horaires d'ouverture chargés : {:?}",
+            calendar.schedules.iter().map(|s| s.name.as_str()).collect::<Vec<_>>()
+        );
+        calendar
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.schedules.is_empty()
+    }
+
+    /// Indique si la case (indice de la grille aplatie) est ouverte à l'instant local donné
+    pub fn is_open(&self, cell: usize, instant: u64) -> bool {
+        self.covering[cell].iter().all(|&schedule| self.schedules[schedule].is_open(instant))
+    }
+}
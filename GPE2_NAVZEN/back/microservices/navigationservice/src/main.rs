@@ -6,9 +6,15 @@ mod server;
 mod services;
 mod utils;
 
-use actix_web::{App, HttpServer};
+use actix_web::{web, App, HttpServer};
+use navigation::budget::SearchLimits;
 use server::routes::configure;
+use server::state::{AppState, DataFiles};
+use services::compute::ComputePool;
+use services::route_cache::RouteCache;
 use std::env;
+use std::time::Duration;
+use utils::clock::{parse_datetime, Clock, FixedClock, SystemClock};
 
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
@@ -19,10 +25,50 @@ async fn main() -> std::io::Result<()> {
     // Lire le PORT depuis .env, sinon utiliser 8080 par défaut
     let port: u16 = env::var("PORT").unwrap_or_else(|_| "8080".to_string()).parse().expect("Port invalide");
 
+    // Charger la carte une seule fois, partagée entre tous les workers
+    let landmark_count: usize = env::var("LANDMARK_COUNT").unwrap_or_else(|_| "0".to_string()).parse().expect("LANDMARK_COUNT invalide");
+    let files = DataFiles {
+        surface_info: env::var("SURFACE_INFO_PATH").unwrap_or_else(|_| "data/SurfaceInfo.txt".to_string()),
+        profiles: env::var("PROFILES_PATH").unwrap_or_else(|_| "data/profiles.json".to_string()),
+        closures: env::var("CLOSURES_PATH").unwrap_or_else(|_| "data/closures.json".to_string()),
+        hazards: env::var("HAZARDS_PATH").unwrap_or_else(|_| "data/hazards.json".to_string()),
+        schedules: env::var("SCHEDULES_PATH").unwrap_or_else(|_| "data/schedules.json".to_string()),
+        exits: env::var("EXITS_PATH").unwrap_or_else(|_| "data/exits.json".to_string()),
+    };
+    let meters_per_cell: f32 = env::var("METERS_PER_CELL").unwrap_or_else(|_| "0.5".to_string()).parse().expect("METERS_PER_CELL invalide");
+
+    // Limites de chaque recherche, pour qu'une requête pathologique n'occupe pas un worker indéfiniment (0 : pas de limite)
+    let max_nodes: usize = env::var("SEARCH_MAX_NODES").unwrap_or_else(|_| "5000000".to_string()).parse().expect("SEARCH_MAX_NODES invalide");
+    let timeout_ms: u64 = env::var("SEARCH_TIMEOUT_MS").unwrap_or_else(|_| "5000".to_string()).parse().expect("SEARCH_TIMEOUT_MS invalide");
+    let search_limits = SearchLimits {
+        max_nodes: (max_nodes > 0).then_some(max_nodes),
+        timeout: (timeout_ms > 0).then(|| Duration::from_millis(timeout_ms)),
+    };
+
+    // Les recherches tournent sur un pool dédié ; au-delà de COMPUTE_QUEUE_SIZE recherches en cours ou en attente, 503
+    let compute_threads: usize = env::var("COMPUTE_THREADS").unwrap_or_else(|_| "0".to_string()).parse().expect("COMPUTE_THREADS invalide");
+    let compute_queue: usize = env::var("COMPUTE_QUEUE_SIZE").unwrap_or_else(|_| "64".to_string()).parse().expect("COMPUTE_QUEUE_SIZE invalide");
+    let compute = ComputePool::new(compute_threads, compute_queue);
+
+    // Cache LRU des itinéraires de /navigate, vidé à chaque changement des fermetures ou des dangers
+    let cache_enabled: bool = env::var("CACHE_ENABLED").unwrap_or_else(|_| "true".to_string()).parse().expect("CACHE_ENABLED invalide");
+    let cache_size: usize = env::var("ROUTE_CACHE_SIZE").unwrap_or_else(|_| "1024".to_string()).parse().expect("ROUTE_CACHE_SIZE invalide");
+    let route_cache = RouteCache::new(cache_enabled, cache_size);
+
+    // Heure locale du bâtiment ; FIXED_CLOCK (AAAA-MM-JJTHH:MM) fige l'horloge pour rejouer les horaires
+    let utc_offset_minutes: i64 = env::var("UTC_OFFSET_MINUTES").unwrap_or_else(|_| "60".to_string()).parse().expect("UTC_OFFSET_MINUTES invalide");
+    let clock: Box<dyn Clock> = match env::var("FIXED_CLOCK") {
+        Ok(value) => Box::new(FixedClock(parse_datetime(&value, 0).expect("FIXED_CLOCK invalide"))),
+        Err(_) => Box::new(SystemClock { utc_offset_minutes }),
+    };
+
+    let state = web::Data::new(AppState::load(&files, landmark_count, meters_per_cell, search_limits, compute, route_cache, clock));
+
     println!("🚀 Démarrage du serveur sur http://127.0.0.1:{}", port);
 
-    HttpServer::new(|| {
+    HttpServer::new(move || {
         App::new()
+            .app_data(state.clone())
             .configure(configure) // Charge toutes les routes
     })
     .bind(("127.0.0.1", port))?
@@ -0,0 +1,142 @@
+// Itinéraires alternatifs par pénalisation.
+//
+// Une fois le meilleur chemin trouvé, on augmente le coût des cases proches des chemins
+// déjà obtenus et on relance A*. Chaque candidat est ensuite jugé sur les vrais coûts du
+// profil : il est retenu s'il n'est pas trop long par rapport au meilleur (MAX_STRETCH)
+// et s'il ne longe pas trop un chemin déjà retenu (MAX_OVERLAP). "Proche" couvre une
+// bande de PENALTY_RADIUS cases : le même couloir pris une case à côté n'est pas une
+// alternative.
+
+use crate::data::loader::Map;
+use crate::navigation::astar::{astar_pathfinding, path_cost};
+use crate::navigation::profile::CostGrid;
+use log::info;
+
+// Facteur appliqué à chaque passage d'un chemin près d'une case
+const PENALTY_FACTOR: f32 = 2.0;
+const PENALTY_RADIUS: isize = 3;
+
+// Une alternative coûte au plus 50 % de plus que le meilleur chemin
+const MAX_STRETCH: f32 = 1.5;
+
+// Part maximale des cases d'une alternative situées près d'un chemin déjà retenu
+const MAX_OVERLAP: f32 = 0.6;
+
+// Nombre de recherches tentées par alternative demandée
+const ATTEMPTS_PER_ROUTE: usize = 3;
+
+// Cases à moins de PENALTY_RADIUS cases (distance de Tchebychev) d'une case du chemin
+fn band(map: &Map, cells: &[(usize, usize)]) -> Vec<bool> {
+    let mut near = vec![false; map.width * map.height];
+    for &(x, y) in cells {
+        for dy in -PENALTY_RADIUS..=PENALTY_RADIUS {
+            for dx in -PENALTY_RADIUS..=PENALTY_RADIUS {
+                let (nx, ny) = (x as isize + dx, y as isize + dy);
+                if nx >= 0 && ny >= 0 && map.contains(nx as usize, ny as usize) {
+                    near[map.index(nx as usize, ny as usize)] = true;
+                }
+            }
+        }
+    }
+    near
+}
+
+/// Jusqu'à `count` chemins alternatifs au meilleur chemin `best`, du moins coûteux au plus coûteux
+pub fn alternative_paths(map: &Map, costs: &CostGrid, best: &[(usize, usize)], count: usize) -> Vec<Vec<(usize, usize)>> {
+    let (Some(&start), Some(&goal)) = (best.first(), best.last()) else {
+        return vec![];
+    };
+
+    let best_cost = path_cost(costs, best);
+    let mut penalized = costs.clone();
+    let mut retained = band(map, best);
+    let mut alternatives: Vec<(f32, Vec<(usize, usize)>)> = vec![];
+    let mut last = best.to_vec();
+
+    for _ in 0..count * ATTEMPTS_PER_ROUTE {
+        if alternatives.len() == count {
+            break;
+        }
+
+        let near = band(map, &last);
+        penalized.penalize((0..near.len()).filter(|&i| near[i]).map(|i| map.position(i)), PENALTY_FACTOR);
+
+        let Some(cells) = astar_pathfinding(map, &penalized, start, goal) else {
+            break;
+        };
+
+        let cost = path_cost(costs, &cells);
+        let overlap = cells.iter().filter(|&&(x, y)| retained[map.index(x, y)]).count() as f32 / cells.len() as f32;
+        if cost <= best_cost * MAX_STRETCH && overlap <= MAX_OVERLAP {
+            for (r, n) in retained.iter_mut().zip(band(map, &cells)) {
+                *r |= n;
+            }
+            alternatives.push((cost, cells.clone()));
+        }
+        last = cells;
+    }
+
+    info!("🔀 {} alternative(s) retenue(s) sur {} demandée(s)", alternatives.len(), count);
+    alternatives.sort_by(|a, b| a.0.total_cmp(&b.0));
+    alternatives.into_iter().map(|(_, cells)| cells).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::profile::RoutingProfile;
+    use crate::navigation::testing::{assert_valid_path, grid, sample_pairs, shipped_map, test_profiles};
+
+    // Vérifie les garanties d'une liste d'alternatives par rapport au meilleur chemin
+    fn assert_alternatives(map: &Map, costs: &CostGrid, best: &[(usize, usize)], alternatives: &[Vec<(usize, usize)>], count: usize) {
+        let (start, goal) = (best[0], best[best.len() - 1]);
+        let best_cost = path_cost(costs, best);
+        let near_best = band(map, best);
+
+        assert!(alternatives.len() <= count, "{} alternatives pour {} demandées", alternatives.len(), count);
+        for cells in alternatives {
+            assert_valid_path(costs, cells, start, goal);
+            assert_ne!(cells.as_slice(), best, "une alternative reprend le meilleur chemin");
+            assert!(path_cost(costs, cells) <= best_cost * MAX_STRETCH + 1e-3, "alternative trop longue");
+            let overlap = cells.iter().filter(|&&(x, y)| near_best[map.index(x, y)]).count() as f32 / cells.len() as f32;
+            assert!(overlap <= MAX_OVERLAP, "alternative trop proche du meilleur chemin ({:.2})", overlap);
+        }
+        for pair in alternatives.windows(2) {
+            assert!(path_cost(costs, &pair[0]) <= path_cost(costs, &pair[1]), "alternatives non triées par coût");
+        }
+    }
+
+    #[test]
+    fn parallel_corridors_give_alternatives() {
+        // Trois couloirs parallèles reliés aux deux bouts : le central est le plus court
+        let mut rows = vec![".............................."];
+        rows.extend([".############################."; 3]);
+        rows.push("..............................");
+        rows.extend([".############################."; 3]);
+        rows.push("..............................");
+        let map = grid(&rows);
+        let costs = CostGrid::build(&map, &RoutingProfile::default());
+        let best = astar_pathfinding(&map, &costs, (0, 4), (29, 4)).unwrap();
+
+        for count in [1, 2, 5] {
+            let alternatives = alternative_paths(&map, &costs, &best, count);
+            assert!(!alternatives.is_empty(), "les couloirs du haut et du bas sont des alternatives");
+            assert_alternatives(&map, &costs, &best, &alternatives, count);
+        }
+        assert!(alternative_paths(&map, &costs, &best, 0).is_empty());
+    }
+
+    #[test]
+    fn alternatives_respect_stretch_and_overlap() {
+        let map = shipped_map();
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            for (start, goal) in sample_pairs(&costs, 6, 41) {
+                let Some(best) = astar_pathfinding(&map, &costs, start, goal) else {
+                    continue;
+                };
+                assert_alternatives(&map, &costs, &best, &alternative_paths(&map, &costs, &best, 3), 3);
+            }
+        }
+    }
+}
@@ -0,0 +1,142 @@
+// Recherche A* bidirectionnelle : une recherche part du départ, l'autre de l'arrivée
+// (sur les arcs inversés), et chacune développe le côté dont la file est la plus petite.
+//
+// Les deux recherches utilisent le potentiel moyen p(v) = (h(v, arrivée) - h(départ, v)) / 2,
+// cohérent dans les deux sens : elles travaillent sur les mêmes coûts réduits, ce qui
+// rend exact le critère d'arrêt classique de Dijkstra bidirectionnel. On s'arrête dès
+// que la somme des deux plus petites clés atteint le meilleur coût de jonction connu.
+
+use crate::data::loader::Map;
+use crate::navigation::astar::{get_neighbors, get_predecessors, report_outcome};
+use crate::navigation::buffers::{with_buffers, Node, SearchBuffers};
+use crate::navigation::profile::CostGrid;
+use log::{info, warn};
+
+// Développe la prochaine case d'un des deux côtés et met à jour la meilleure jonction ;
+// false si le budget de la recherche est épuisé
+fn expand_side<'m, I: Iterator<Item = (usize, usize, f32)>>(
+    map: &Map,
+    costs: &'m CostGrid,
+    arcs: impl Fn((usize, usize), &'m CostGrid) -> I,
+    side: &mut SearchBuffers,
+    other: &SearchBuffers,
+    potential: impl Fn((usize, usize)) -> f32,
+    best: &mut (f32, Option<usize>),
+) -> bool {
+    let Some(Node { position, .. }) = side.pop() else {
+        return false;
+    };
+    if !side.close(position) {
+        return true;
+    }
+
+    let current = map.position(position);
+    let current_g = side.g(position);
+    for (nx, ny, move_cost) in arcs(current, costs) {
+        let neighbor = map.index(nx, ny);
+        let tentative_g_score = current_g + move_cost;
+
+        if tentative_g_score < side.g(neighbor) {
+            side.set(neighbor, tentative_g_score, Some(position));
+            side.open_set.push(Node { cost: tentative_g_score + potential((nx, ny)), position: neighbor });
+        }
+
+        let through = side.g(neighbor) + other.g(neighbor);
+        if through < best.0 {
+            *best = (through, Some(neighbor));
+        }
+    }
+    true
+}
+
+// Alternative à `astar_pathfinding` pour les longs trajets : même sortie, zone explorée réduite
+pub fn bidirectional_pathfinding(map: &Map, costs: &CostGrid, start: (usize, usize), goal: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    info!("🔎 Démarrage de A* bidirectionnel entre {:?} et {:?}", start, goal);
+
+    if !map.contains(start.0, start.1) || !map.contains(goal.0, goal.1) {
+        warn!("❌ Départ {:?} ou arrivée {:?} hors de la carte", start, goal);
+        return None;
+    }
+
+    let start_index = map.index(start.0, start.1);
+    let goal_index = map.index(goal.0, goal.1);
+    let size = map.width * map.height;
+    let potential = |position: (usize, usize)| (costs.estimate(position, goal) - costs.estimate(start, position)) / 2.0;
+
+    let cells = with_buffers(size, |forward| {
+        with_buffers(size, |backward| {
+            forward.set(start_index, 0.0, None);
+            forward.open_set.push(Node { cost: potential(start), position: start_index });
+            backward.set(goal_index, 0.0, None);
+            backward.open_set.push(Node { cost: -potential(goal), position: goal_index });
+
+            let mut best = if start_index == goal_index { (0.0, Some(start_index)) } else { (f32::INFINITY, None) };
+
+            while let (Some(top_forward), Some(top_backward)) = (forward.open_set.peek(), backward.open_set.peek()) {
+                if top_forward.cost + top_backward.cost >= best.0 {
+                    break;
+                }
+
+                let expanded = if forward.open_set.len() <= backward.open_set.len() {
+                    expand_side(map, costs, get_neighbors, forward, backward, potential, &mut best)
+                } else {
+                    expand_side(map, costs, get_predecessors, backward, forward, |p| -potential(p), &mut best)
+                };
+                // Recherche interrompue : la jonction connue n'est pas forcément la meilleure
+                if !expanded {
+                    best.1 = None;
+                    break;
+                }
+            }
+
+            // Jonction des deux demi-chemins
+            best.1.map(|meeting| {
+                let mut cells = forward.trace(meeting);
+                let mut current = meeting;
+                while let Some(next) = backward.parent(current) {
+                    cells.push(next);
+                    current = next;
+                }
+                cells.into_iter().map(|i| map.position(i)).collect()
+            })
+        })
+    });
+
+    report_outcome(start, goal, cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::testing::{assert_matches_astar, grid, sample_pairs, shipped_map, test_profiles};
+
+    #[test]
+    fn same_cost_as_astar_on_shipped_map() {
+        let map = shipped_map();
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            for (start, goal) in sample_pairs(&costs, 25, 30) {
+                assert_matches_astar(&map, &costs, start, goal, bidirectional_pathfinding(&map, &costs, start, goal));
+            }
+        }
+    }
+
+    // Coûts asymétriques (le coût dépend de la case d'arrivée) : la recherche arrière doit en tenir compte
+    #[test]
+    fn same_cost_as_astar_on_weighted_grid() {
+        let map = grid(&[
+            "..oooo....",
+            ".#oooo.##.",
+            ".#..E.#...",
+            "..##AA..#.",
+            "1.#....#..",
+            "11..##....",
+        ]);
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            for (start, goal) in sample_pairs(&costs, 60, 3) {
+                assert_matches_astar(&map, &costs, start, goal, bidirectional_pathfinding(&map, &costs, start, goal));
+            }
+        }
+    }
+}
@@ -0,0 +1,170 @@
+// Limites d'une recherche : nombre de nœuds, durée et annulation par le client.
+//
+// Le budget est installé pour le thread courant le temps d'une recherche (`with_budget`).
+// La limite de nœuds vaut pour chaque recherche, mais la durée court pour toute la requête :
+// l'échéance est fixée à la création du budget, et partagée par toutes ses recherches.
+// `SearchBuffers::pop` le consulte avant chaque nœud et vide la file dès qu'il est épuisé :
+// l'algorithme s'arrête comme s'il n'y avait plus de chemin, et `with_budget` signale
+// l'interruption pour qu'elle ne soit pas confondue avec une absence de chemin.
+
+use std::cell::RefCell;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use log::warn;
+use serde::Serialize;
+
+// L'horloge et le jeton d'annulation ne sont consultés que tous les CHECK_INTERVAL nœuds
+const CHECK_INTERVAL: usize = 256;
+
+/// Limites d'une requête (None : pas de limite) ; une tournée ou une recherche dépendant de
+/// l'heure comptent pour une seule recherche
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SearchLimits {
+    pub max_nodes: Option<usize>,  // Nœuds retirés de la file, par recherche
+    pub timeout: Option<Duration>, // Durée de la requête, toutes recherches confondues
+}
+
+/// Jeton partagé entre une requête et ses recherches ; annulé quand le client se déconnecte
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::Relaxed);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::Relaxed)
+    }
+}
+
+/// Annule le jeton à sa destruction : gardé par le handler, il annule la recherche si actix
+/// abandonne la requête (client déconnecté)
+pub struct CancelOnDrop(CancellationToken);
+
+impl Drop for CancelOnDrop {
+    fn drop(&mut self) {
+        self.0.cancel();
+    }
+}
+
+/// Cause de l'interruption d'une recherche
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AbortReason {
+    NodeLimit,
+    Timeout,
+    Cancelled,
+}
+
+/// Budget d'une requête : limites, échéance et jeton d'annulation
+#[derive(Debug, Clone, Default)]
+pub struct SearchBudget {
+    pub limits: SearchLimits,
+    pub token: CancellationToken,
+    deadline: Option<Instant>, // Fin de la durée accordée à la requête
+}
+
+impl SearchBudget {
+    /// Budget d'une requête reçue maintenant : sa durée court dès ici, attente du pool de calcul comprise
+    pub fn new(limits: SearchLimits) -> Self {
+        Self { limits, token: CancellationToken::default(), deadline: limits.timeout.map(|timeout| Instant::now() + timeout) }
+    }
+
+    /// Garde qui annule les recherches de ce budget quand elle est détruite
+    pub fn cancel_on_drop(&self) -> CancelOnDrop {
+        CancelOnDrop(self.token.clone())
+    }
+}
+
+// Budget en cours sur ce thread
+struct ActiveBudget {
+    max_nodes: usize,
+    deadline: Option<Instant>,
+    token: CancellationToken,
+    popped: usize,
+    aborted: Option<AbortReason>,
+}
+
+thread_local! {
+    static ACTIVE: RefCell<Option<ActiveBudget>> = const { RefCell::new(None) };
+}
+
+/// Exécute `search` sous le budget donné ; retourne aussi la cause de l'interruption, le cas échéant
+pub(crate) fn with_budget<R>(budget: &SearchBudget, search: impl FnOnce() -> R) -> (R, Option<AbortReason>) {
+    let active = ActiveBudget {
+        max_nodes: budget.limits.max_nodes.unwrap_or(usize::MAX),
+        deadline: budget.deadline,
+        token: budget.token.clone(),
+        popped: 0,
+        aborted: None,
+    };
+    let previous = ACTIVE.with(|slot| slot.replace(Some(active)));
+
+    let result = search();
+
+    let active = ACTIVE.with(|slot| slot.replace(previous));
+    (result, active.and_then(|active| active.aborted))
+}
+
+/// Compte un nœud retiré de la file ; true si le budget en cours est épuisé (jamais sans budget)
+pub(crate) fn exhausted() -> bool {
+    ACTIVE.with(|slot| {
+        let mut slot = slot.borrow_mut();
+        let Some(active) = slot.as_mut() else {
+            return false;
+        };
+        if active.aborted.is_some() {
+            return true;
+        }
+
+        active.popped += 1;
+        let reason = if active.popped > active.max_nodes {
+            Some(AbortReason::NodeLimit)
+        } else if active.popped % CHECK_INTERVAL != 0 {
+            None
+        } else if active.token.is_cancelled() {
+            Some(AbortReason::Cancelled)
+        } else if active.deadline.is_some_and(|deadline| Instant::now() >= deadline) {
+            Some(AbortReason::Timeout)
+        } else {
+            None
+        };
+
+        if let Some(reason) = reason {
+            warn!("⛔ Recherche interrompue ({:?}) après {} nœuds", reason, active.popped);
+            active.aborted = Some(reason);
+        }
+        active.aborted.is_some()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::thread;
+
+    // Retire des nœuds jusqu'à l'épuisement du budget, au plus `count`
+    fn pop_nodes(count: usize) -> bool {
+        (0..count).any(|_| exhausted())
+    }
+
+    #[test]
+    fn searches_of_a_request_share_one_deadline() {
+        let budget = SearchBudget::new(SearchLimits { max_nodes: None, timeout: Some(Duration::from_millis(50)) });
+        assert_eq!(with_budget(&budget, || pop_nodes(CHECK_INTERVAL)), (false, None));
+
+        // Une recherche lancée après l'échéance est interrompue, même si elle vient de commencer
+        thread::sleep(Duration::from_millis(60));
+        assert_eq!(with_budget(&budget, || pop_nodes(CHECK_INTERVAL)), (true, Some(AbortReason::Timeout)));
+    }
+
+    #[test]
+    fn node_limit_applies_to_each_search() {
+        let budget = SearchBudget::new(SearchLimits { max_nodes: Some(10), timeout: None });
+        assert_eq!(with_budget(&budget, || pop_nodes(10)), (false, None));
+        assert_eq!(with_budget(&budget, || pop_nodes(10)), (false, None));
+        assert_eq!(with_budget(&budget, || pop_nodes(11)), (true, Some(AbortReason::NodeLimit)));
+    }
+}
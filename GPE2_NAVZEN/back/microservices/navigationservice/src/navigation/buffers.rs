@@ -0,0 +1,226 @@
+use std::cell::{Cell, RefCell};
+use std::cmp::Ordering;
+use std::collections::BinaryHeap;
+use crate::navigation::budget::exhausted;
+
+// Indice réservé pour "aucun parent" (case de départ)
+const NO_PARENT: u32 = u32::MAX;
+
+// Nœud de la file de priorité, repéré par son indice dans la grille aplatie
+#[derive(Debug, PartialEq)]
+pub(crate) struct Node {
+    pub cost: f32,
+    pub position: usize,
+}
+
+impl Eq for Node {}
+
+impl Ord for Node {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.cost.total_cmp(&other.cost).reverse()
+    }
+}
+
+impl PartialOrd for Node {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Tampons de travail d'une recherche sur grille aplatie.
+///
+/// Les cases ne sont jamais remises à zéro entre deux recherches : chaque
+/// recherche incrémente `generation`, et une valeur n'est lue que si son
+/// tampon porte la génération courante.
+pub(crate) struct SearchBuffers {
+    generation: u32,
+    g_score: Vec<f32>,
+    came_from: Vec<u32>,
+    seen: Vec<u32>,
+    closed: Vec<u32>,
+    expanded: usize,
+    pub open_set: BinaryHeap<Node>,
+}
+
+impl SearchBuffers {
+    fn new() -> Self {
+        Self {
+            generation: 0,
+            g_score: Vec::new(),
+            came_from: Vec::new(),
+            seen: Vec::new(),
+            closed: Vec::new(),
+            expanded: 0,
+            open_set: BinaryHeap::new(),
+        }
+    }
+
+    // Prépare les tampons pour une nouvelle recherche sur `size` cases
+    fn prepare(&mut self, size: usize) {
+        if self.seen.len() < size {
+            self.g_score.resize(size, f32::INFINITY);
+            self.came_from.resize(size, NO_PARENT);
+            self.seen.resize(size, 0);
+            self.closed.resize(size, 0);
+        }
+
+        self.generation = self.generation.wrapping_add(1);
+        if self.generation == 0 {
+            // Débordement du compteur : on repart d'un état propre
+            self.seen.iter_mut().for_each(|stamp| *stamp = 0);
+            self.closed.iter_mut().for_each(|stamp| *stamp = 0);
+            self.generation = 1;
+        }
+
+        self.expanded = 0;
+        self.open_set.clear();
+    }
+
+    /// Coût connu pour atteindre une case (`INFINITY` si jamais atteinte)
+    pub fn g(&self, index: usize) -> f32 {
+        if self.seen[index] == self.generation {
+            self.g_score[index]
+        } else {
+            f32::INFINITY
+        }
+    }
+
+    /// Enregistre un meilleur coût pour une case et son prédécesseur
+    pub fn set(&mut self, index: usize, g: f32, parent: Option<usize>) {
+        self.seen[index] = self.generation;
+        self.g_score[index] = g;
+        self.came_from[index] = parent.map_or(NO_PARENT, |p| p as u32);
+    }
+
+    /// Prédécesseur d'une case sur le meilleur chemin connu
+    pub fn parent(&self, index: usize) -> Option<usize> {
+        if self.seen[index] != self.generation || self.came_from[index] == NO_PARENT {
+            return None;
+        }
+        Some(self.came_from[index] as usize)
+    }
+
+    /// Retire le nœud le moins coûteux de la file ; None si elle est vide ou si le budget
+    /// de la recherche en cours est épuisé (voir `navigation::budget`)
+    pub fn pop(&mut self) -> Option<Node> {
+        if exhausted() {
+            return None;
+        }
+        self.open_set.pop()
+    }
+
+    /// Marque une case comme explorée ; retourne `false` si elle l'était déjà
+    pub fn close(&mut self, index: usize) -> bool {
+        if self.closed[index] == self.generation {
+            return false;
+        }
+        self.closed[index] = self.generation;
+        self.expanded += 1;
+        true
+    }
+
+    /// Compte un nœud développé par une recherche qui ne passe pas par `close`
+    /// (plusieurs étiquettes par case, voir `navigation::timed`)
+    pub fn count_expansion(&mut self) {
+        self.expanded += 1;
+    }
+
+    /// Indique si une case a été explorée par la recherche en cours
+    pub fn is_closed(&self, index: usize) -> bool {
+        self.closed[index] == self.generation
+    }
+
+    /// Remonte les prédécesseurs depuis `goal` et retourne la suite d'indices depuis le départ
+    pub fn trace(&self, goal: usize) -> Vec<usize> {
+        let mut cells = vec![goal];
+        let mut current = goal;
+        while let Some(prev) = self.parent(current) {
+            cells.push(prev);
+            current = prev;
+        }
+        cells.reverse();
+        cells
+    }
+}
+
+thread_local! {
+    // Réserve de tampons propre à chaque thread de travail
+    static POOL: RefCell<Vec<SearchBuffers>> = const { RefCell::new(Vec::new()) };
+    // Nombre total de nœuds développés sur ce thread (compteur croissant)
+    static EXPANDED: Cell<usize> = const { Cell::new(0) };
+}
+
+/// Nombre de nœuds développés jusqu'ici par les recherches du thread courant.
+///
+/// La différence entre deux lectures donne le travail d'une recherche, quel que soit l'algorithme.
+pub(crate) fn expanded_nodes() -> usize {
+    EXPANDED.with(Cell::get)
+}
+
+/// Prête un jeu de tampons préparé pour `size` cases le temps de `f`.
+///
+/// Les tampons sont pris dans la réserve du thread courant puis y sont rendus,
+/// ce qui permet aussi d'en emprunter plusieurs à la fois (recherche bidirectionnelle).
+pub(crate) fn with_buffers<R>(size: usize, f: impl FnOnce(&mut SearchBuffers) -> R) -> R {
+    let mut buffers = POOL
+        .with(|pool| pool.borrow_mut().pop())
+        .unwrap_or_else(SearchBuffers::new);
+    buffers.prepare(size);
+
+    let result = f(&mut buffers);
+
+    EXPANDED.with(|count| count.set(count.get() + buffers.expanded));
+    POOL.with(|pool| pool.borrow_mut().push(buffers));
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::astar::astar_pathfinding;
+    use crate::navigation::profile::{CostGrid, RoutingProfile};
+    use crate::navigation::testing::{grid, sample_pairs, shipped_map};
+
+    // Les recherches successives d'un même thread réutilisent les tampons : elles doivent
+    // donner exactement les chemins obtenus avec des tampons neufs (nouveau thread)
+    #[test]
+    fn repeated_searches_ignore_previous_generations() {
+        let map = shipped_map();
+        let costs = CostGrid::build(&map, &RoutingProfile::default());
+        let small = grid(&["....", ".##.", "...."]);
+        let small_costs = CostGrid::build(&small, &RoutingProfile::default());
+        let pairs = sample_pairs(&costs, 40, 26);
+
+        let fresh: Vec<_> = pairs
+            .iter()
+            .map(|&(start, goal)| {
+                std::thread::scope(|scope| scope.spawn(|| astar_pathfinding(&map, &costs, start, goal)).join().unwrap())
+            })
+            .collect();
+
+        for (&(start, goal), expected) in pairs.iter().zip(&fresh) {
+            // Une recherche sur une grille plus petite entre deux : tampons plus grands que nécessaire
+            assert!(astar_pathfinding(&small, &small_costs, (0, 0), (3, 2)).is_some());
+            assert_eq!(&astar_pathfinding(&map, &costs, start, goal), expected, "{:?} → {:?}", start, goal);
+        }
+    }
+
+    #[test]
+    fn stale_values_are_hidden_after_generation_overflow() {
+        let mut buffers = SearchBuffers::new();
+        buffers.prepare(4);
+        buffers.generation = u32::MAX - 1;
+        buffers.prepare(4);
+        buffers.set(1, 2.0, Some(0));
+        assert!(buffers.close(1));
+
+        // Débordement (la génération repart à 1 après remise à zéro des tampons), puis génération 2
+        for _ in 0..2 {
+            buffers.prepare(4);
+            assert_eq!(buffers.g(1), f32::INFINITY);
+            assert_eq!(buffers.parent(1), None);
+            assert!(buffers.close(1));
+        }
+        assert_eq!(buffers.generation, 2);
+    }
+}
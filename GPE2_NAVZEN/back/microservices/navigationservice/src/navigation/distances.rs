@@ -0,0 +1,103 @@
+// Matrice des distances entre salles.
+//
+// Pour chaque salle, un Dijkstra vers toutes les cases part de toutes ses cases à la fois
+// (comme `/navigate?from_room=`) ; la première case atteinte de chaque autre salle donne
+// l'itinéraire le moins coûteux entre les deux, dont on garde la longueur réelle et la durée
+// (pas le coût, qui mêle les préférences du profil). Les salles sont traitées en parallèle avec rayon.
+
+use std::collections::HashMap;
+use std::time::Instant;
+use crate::data::loader::Map;
+use crate::navigation::isochrone::{all_rooms, cells_within, reachable_rooms, Limit};
+use crate::navigation::profile::CostGrid;
+use crate::navigation::travel_time::TravelTime;
+use log::info;
+use rayon::prelude::*;
+
+/// Longueur et durée de l'itinéraire le moins coûteux entre chaque paire de salles, pour un profil
+pub struct DistanceMatrix {
+    pub version: u64,                 // Version des coûts (fermetures et dangers) ayant servi au calcul
+    pub rooms: Vec<usize>,            // Salles par ordre croissant : ligne et colonne de chaque salle
+    positions: HashMap<usize, usize>, // Ligne (et colonne) de chaque salle
+    lengths: Vec<f32>,                // rooms.len() × rooms.len(), en cases ; INFINITY si la salle d'arrivée est inaccessible
+    seconds: Vec<f32>,                // Durées, mêmes dimensions
+}
+
+impl DistanceMatrix {
+    pub fn build(map: &Map, costs: &CostGrid, travel: &TravelTime, version: u64) -> Self {
+        let started = Instant::now();
+        let rooms = all_rooms(map);
+        let positions: HashMap<usize, usize> = rooms.iter().enumerate().map(|(position, &room)| (room, position)).collect();
+
+        let rows: Vec<(Vec<f32>, Vec<f32>)> = rooms
+            .par_iter()
+            .map(|&room| {
+                let reached = cells_within(map, costs, travel, &map.room_cells(room), Limit::Length(f32::INFINITY));
+                let mut lengths = vec![f32::INFINITY; rooms.len()];
+                let mut seconds = vec![f32::INFINITY; rooms.len()];
+                for (target, cell) in reachable_rooms(map, &reached) {
+                    lengths[positions[&target]] = cell.length;
+                    seconds[positions[&target]] = cell.seconds;
+                }
+                (lengths, seconds)
+            })
+            .collect();
+
+        info!("📏 Matrice des distances : {} salles en {:.2} ms", rooms.len(), started.elapsed().as_secs_f64() * 1000.0);
+        let (lengths, seconds): (Vec<Vec<f32>>, Vec<Vec<f32>>) = rows.into_iter().unzip();
+        Self { version, rooms, positions, lengths: lengths.concat(), seconds: seconds.concat() }
+    }
+
+    /// Ligne (et colonne) d'une salle ; None si elle n'existe pas
+    pub fn position(&self, room: usize) -> Option<usize> {
+        self.positions.get(&room).copied()
+    }
+
+    /// Longueur (en cases) de l'itinéraire entre les salles des positions `from` et `to` ; None si inaccessible
+    pub fn length(&self, from: usize, to: usize) -> Option<f32> {
+        Some(self.lengths[from * self.rooms.len() + to]).filter(|length| length.is_finite())
+    }
+
+    /// Durée (s) de l'itinéraire entre les salles des positions `from` et `to` ; None si inaccessible
+    pub fn seconds(&self, from: usize, to: usize) -> Option<f32> {
+        Some(self.seconds[from * self.rooms.len() + to]).filter(|seconds| seconds.is_finite())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::astar::{astar_pathfinding, reconstruct_path, step_length};
+    use crate::navigation::profile::RoutingProfile;
+    use crate::navigation::testing::grid;
+    use crate::utils::i18n::Lang;
+
+    #[test]
+    fn reports_length_and_time_not_cost() {
+        // Un détour par le couloir évite l'extérieur, six fois plus coûteux
+        let map = grid(&[
+            "1oooo2",
+            ".####.",
+            "......",
+            "3#####",
+        ]);
+        let profile = RoutingProfile { exterieur: Some(6.0), ..RoutingProfile::default() };
+        let costs = CostGrid::build(&map, &profile);
+        let travel = TravelTime::new(&profile, 0.5);
+        let matrix = DistanceMatrix::build(&map, &costs, &travel, 7);
+
+        assert_eq!(matrix.version, 7);
+        assert_eq!(matrix.rooms, vec![1, 2, 3]);
+        let (one, two, three) = (matrix.position(1).unwrap(), matrix.position(2).unwrap(), matrix.position(3).unwrap());
+        assert_eq!(matrix.position(4), None);
+
+        let detour = astar_pathfinding(&map, &costs, (0, 0), (5, 0)).unwrap();
+        assert!(detour.contains(&(2, 2)), "l'itinéraire passe par l'extérieur");
+        let length: f32 = detour.windows(2).map(|pair| step_length(pair[0], pair[1])).sum();
+        let duration: f32 = reconstruct_path(&map, &costs, &travel, &detour, Lang::Fr).iter().map(|segment| segment.time_s).sum();
+        assert!((matrix.length(one, two).unwrap() - length).abs() < 1e-3);
+        assert!((matrix.seconds(one, two).unwrap() - duration).abs() < 1e-3);
+        assert_eq!(matrix.length(one, one), Some(0.0));
+        assert_eq!(matrix.length(one, three), Some(3.0));
+    }
+}
@@ -0,0 +1,149 @@
+// Champs d'évacuation : pour chaque case praticable, le pas suivant vers la sortie sûre la
+// moins coûteuse à atteindre.
+//
+// Un seul Dijkstra en sens inverse, lancé depuis toutes les sorties à la fois, donne pour
+// chaque case son successeur sur le meilleur chemin vers une sortie. Le champ complet est
+// envoyé aux clients, qui peuvent ainsi guider l'évacuation même sans réseau. La durée
+// d'évacuation de chaque case est celle du trajet réel en suivant le champ (`TravelTime`),
+// sans la pénalité qui écarte les chemins des zones dangereuses.
+
+use crate::data::loader::Map;
+use crate::navigation::astar::{get_predecessors, step_length, MOVES};
+use crate::navigation::buffers::{with_buffers, Node};
+use crate::navigation::profile::CostGrid;
+use crate::navigation::travel_time::TravelTime;
+use log::{info, warn};
+
+// Multiplicateur appliqué aux zones dangereuses : on ne les traverse qu'en dernier recours,
+// mais une personne qui s'y trouve reçoit tout de même un chemin pour en sortir
+pub const HAZARD_PENALTY: f32 = 50.0;
+
+// Codes d'une case du champ : indice dans `MOVES` (0 à 7), sortie atteinte ou aucun chemin
+const AT_EXIT: u8 = MOVES.len() as u8;
+const NO_ROUTE: u8 = u8::MAX;
+
+/// Direction d'évacuation de chaque case et durée restante jusqu'à la sortie
+pub struct FlowField {
+    directions: Vec<u8>,
+    seconds: Vec<f32>, // INFINITY si aucune sortie n'est accessible
+}
+
+impl FlowField {
+    /// Calcule le champ depuis les cases de sortie `exits` ; les sorties infranchissables
+    /// pour le profil sont ignorées
+    pub fn build(map: &Map, costs: &CostGrid, travel: &TravelTime, exits: &[(usize, usize)]) -> Self {
+        let size = map.width * map.height;
+        let mut field = Self { directions: vec![NO_ROUTE; size], seconds: vec![f32::INFINITY; size] };
+
+        let exits: Vec<(usize, usize)> = exits.iter().copied().filter(|&(x, y)| costs.multiplier(x, y).is_some()).collect();
+        if exits.is_empty() {
+            warn!("⚠️ Aucune sortie accessible : le champ d'évacuation est vide");
+            return field;
+        }
+
+        with_buffers(size, |buffers| {
+            for &(x, y) in &exits {
+                let index = map.index(x, y);
+                buffers.open_set.push(Node { cost: 0.0, position: index });
+                buffers.set(index, 0.0, None);
+            }
+
+            while let Some(Node { position, .. }) = buffers.pop() {
+                if !buffers.close(position) {
+                    continue;
+                }
+
+                let current = map.position(position);
+                let current_g = buffers.g(position);
+                // Le pas suivant a été fermé avant cette case : sa durée est connue
+                (field.directions[position], field.seconds[position]) = match buffers.parent(position) {
+                    None => (AT_EXIT, 0.0),
+                    Some(next) => {
+                        let next_cell = map.position(next);
+                        let step = travel.step_seconds(map, current, next_cell, step_length(current, next_cell));
+                        (direction_code(current, next_cell), field.seconds[next] + step)
+                    }
+                };
+
+                // Le parent d'une case est la case où l'on va ensuite : le pas suivant vers la sortie
+                for (px, py, move_cost) in get_predecessors(current, costs) {
+                    let predecessor = map.index(px, py);
+                    let tentative_g_score = current_g + move_cost;
+
+                    if tentative_g_score < buffers.g(predecessor) {
+                        buffers.set(predecessor, tentative_g_score, Some(position));
+                        buffers.open_set.push(Node { cost: tentative_g_score, position: predecessor });
+                    }
+                }
+            }
+        });
+
+        info!("🚨 Champ d'évacuation calculé depuis {} cases de sortie", exits.len());
+        field
+    }
+
+    /// Durée d'évacuation (s) la plus longue parmi les cases qui ont un chemin
+    pub fn max_seconds(&self) -> f32 {
+        self.seconds.iter().copied().filter(|seconds| seconds.is_finite()).fold(0.0, f32::max)
+    }
+
+    /// Nombre de cases praticables pour le profil sans aucun chemin vers une sortie
+    pub fn stranded_cells(&self, costs: &CostGrid, map: &Map) -> usize {
+        (0..self.directions.len())
+            .filter(|&index| self.directions[index] == NO_ROUTE)
+            .filter(|&index| {
+                let (x, y) = map.position(index);
+                costs.multiplier(x, y).is_some()
+            })
+            .count()
+    }
+
+    /// Champ ligne par ligne, une lettre par case : `0` à `7` pour l'indice du déplacement
+    /// dans `moves()`, `S` sur une sortie, `.` sans chemin (mur ou zone coupée des sorties)
+    pub fn rows(&self, map: &Map) -> Vec<String> {
+        (0..map.height)
+            .map(|y| {
+                (0..map.width)
+                    .map(|x| match self.directions[map.index(x, y)] {
+                        AT_EXIT => 'S',
+                        NO_ROUTE => '.',
+                        code => char::from(b'0' + code),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+/// Déplacements (dx, dy) désignés par les chiffres du champ
+pub fn moves() -> Vec<(isize, isize)> {
+    MOVES.iter().map(|&(dx, dy, _)| (dx, dy)).collect()
+}
+
+// Indice dans `MOVES` du pas de `from` vers la case voisine `to`
+fn direction_code(from: (usize, usize), to: (usize, usize)) -> u8 {
+    let (dx, dy) = (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize);
+    MOVES.iter().position(|&(mx, my, _)| (mx, my) == (dx, dy)).map_or(NO_ROUTE, |code| code as u8)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::profile::RoutingProfile;
+    use crate::navigation::testing::grid;
+
+    #[test]
+    fn hazard_penalty_does_not_inflate_evacuation_time() {
+        let map = grid(&["1....o"]);
+        let profile = RoutingProfile::default();
+        let mut costs = CostGrid::build(&map, &profile);
+        costs.penalize([(2, 0), (3, 0)], HAZARD_PENALTY);
+        let travel = TravelTime::new(&profile, 0.5);
+
+        let field = FlowField::build(&map, &costs, &travel, &[(5, 0)]);
+        let expected: f32 = (0..5).map(|x| travel.step_seconds(&map, (x, 0), (x + 1, 0), 1.0)).sum();
+        assert!((field.max_seconds() - expected).abs() < 1e-3, "{} au lieu de {}", field.max_seconds(), expected);
+        // Toujours vers la droite (déplacement n°0), à travers la zone dangereuse
+        assert_eq!(field.rows(&map), vec!["00000S".to_string()]);
+    }
+}
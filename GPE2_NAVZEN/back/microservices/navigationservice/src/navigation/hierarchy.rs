@@ -0,0 +1,422 @@
+// Recherche hiérarchique (style HPA*) sur un graphe de régions et de portes.
+//
+// La carte est découpée en régions : chaque salle forme une région, les couloirs
+// et l'extérieur sont découpés en sections de `SECTION_SIZE` cases. Les portes sont
+// des cases de part et d'autre d'une frontière entre deux régions ; les distances
+// entre portes d'une même région sont précalculées pour un profil donné. Une requête cherche d'abord un
+// itinéraire de porte en porte, puis l'affine case par case entre deux portes successives.
+//
+// Le résultat est approché : le trajet doit passer par une porte à chaque changement de région
+// (une ou deux par entrée), ce qui ajoute des détours, jusqu'à ~20 % sur la carte livrée. Le
+// surcoût maximal possible est renvoyé au client avec les mesures de la recherche
+// (`SearchStats::max_extra_cost`).
+
+use std::collections::{BTreeMap, HashMap, HashSet, VecDeque};
+use crate::data::loader::{Map, SurfaceType};
+use crate::navigation::astar::{astar_cells, astar_pathfinding, get_neighbors, get_predecessors, heuristic};
+use crate::navigation::buffers::{with_buffers, Node, SearchBuffers};
+use crate::navigation::profile::CostGrid;
+use log::{info, warn};
+
+// Taille (en cases) des sections découpées dans les couloirs et l'extérieur
+const SECTION_SIZE: usize = 16;
+// Au-delà de cette longueur, une entrée reçoit une porte à chacune de ses extrémités
+const WIDE_ENTRANCE: usize = 6;
+// Région des murs
+const NO_REGION: u32 = u32::MAX;
+
+#[derive(Debug, Clone)]
+pub struct Region {
+    pub doors: Vec<usize>, // Indices des portes situées dans la région
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct Door {
+    pub cell: usize,
+}
+
+pub struct RegionGraph {
+    region_of: Vec<u32>,
+    pub regions: Vec<Region>,
+    pub doors: Vec<Door>,
+    edges: Vec<Vec<(usize, f32)>>, // Pour chaque porte : (porte voisine, coût)
+}
+
+// Clé de regroupement d'une case : surface et section (les salles ne sont pas découpées)
+fn region_key(map: &Map, costs: &CostGrid, x: usize, y: usize) -> Option<(SurfaceType, usize, usize)> {
+    costs.multiplier(x, y)?;
+    match map.grid[y][x] {
+        SurfaceType::Mur => None,
+        SurfaceType::Room(id) => Some((SurfaceType::Room(id), 0, 0)),
+        surface => Some((surface, x / SECTION_SIZE, y / SECTION_SIZE)),
+    }
+}
+
+impl RegionGraph {
+    /// Précalcule les régions, les portes et les distances entre portes d'une même région
+    pub fn build(map: &Map, costs: &CostGrid) -> Self {
+        let size = map.width * map.height;
+        let mut graph = Self { region_of: vec![NO_REGION; size], regions: vec![], doors: vec![], edges: vec![] };
+
+        graph.label_regions(map, costs);
+        graph.place_doors(map, costs);
+        graph.link_doors(map, costs);
+
+        info!("🧩 Graphe de régions construit : {} régions, {} portes", graph.regions.len(), graph.doors.len());
+        graph
+    }
+
+    /// Région contenant la case (x, y), None pour un mur
+    pub fn region_at(&self, map: &Map, x: usize, y: usize) -> Option<usize> {
+        if !map.contains(x, y) {
+            return None;
+        }
+        match self.region_of[map.index(x, y)] {
+            NO_REGION => None,
+            region => Some(region as usize),
+        }
+    }
+
+    // Composantes connexes (8 directions) de cases partageant la même clé
+    fn label_regions(&mut self, map: &Map, costs: &CostGrid) {
+        for index in 0..self.region_of.len() {
+            if self.region_of[index] != NO_REGION {
+                continue;
+            }
+            let (x, y) = map.position(index);
+            let Some(key) = region_key(map, costs, x, y) else {
+                continue;
+            };
+
+            let region = self.regions.len() as u32;
+            self.regions.push(Region { doors: vec![] });
+            self.region_of[index] = region;
+
+            let mut queue = VecDeque::from([index]);
+            while let Some(cell) = queue.pop_front() {
+                for (nx, ny, _) in get_neighbors(map.position(cell), costs) {
+                    let neighbor = map.index(nx, ny);
+                    if self.region_of[neighbor] == NO_REGION && region_key(map, costs, nx, ny) == Some(key) {
+                        self.region_of[neighbor] = region;
+                        queue.push_back(neighbor);
+                    }
+                }
+            }
+        }
+    }
+
+    // Place les portes sur chaque entrée (portion continue de frontière entre deux régions)
+    fn place_doors(&mut self, map: &Map, costs: &CostGrid) {
+        let mut borders: BTreeMap<(u32, u32), Vec<usize>> = BTreeMap::new();
+        for index in 0..self.region_of.len() {
+            let region = self.region_of[index];
+            if region == NO_REGION {
+                continue;
+            }
+            for (nx, ny, _) in get_neighbors(map.position(index), costs) {
+                let other = self.region_of[map.index(nx, ny)];
+                if other != region && region < other {
+                    let cells = borders.entry((region, other)).or_default();
+                    if cells.last() != Some(&index) {
+                        cells.push(index);
+                    }
+                }
+            }
+        }
+
+        let mut door_at: HashMap<usize, usize> = HashMap::new();
+        for ((region, other), cells) in borders {
+            for entrance in split_entrances(map, costs, &cells) {
+                let picked = if entrance.len() > WIDE_ENTRANCE {
+                    vec![entrance[0], entrance[entrance.len() - 1]]
+                } else {
+                    vec![entrance[entrance.len() / 2]]
+                };
+
+                for inside in picked {
+                    let Some((outside, cost_out)) = get_neighbors(map.position(inside), costs)
+                        .map(|(nx, ny, cost)| (map.index(nx, ny), cost))
+                        .find(|&(cell, _)| self.region_of[cell] == other)
+                    else {
+                        continue;
+                    };
+                    let cost_in = get_neighbors(map.position(outside), costs)
+                        .find(|&(nx, ny, _)| map.index(nx, ny) == inside)
+                        .map_or(f32::INFINITY, |(_, _, cost)| cost);
+
+                    let a = self.door(&mut door_at, inside, region as usize);
+                    let b = self.door(&mut door_at, outside, other as usize);
+                    self.edges[a].push((b, cost_out));
+                    self.edges[b].push((a, cost_in));
+                }
+            }
+        }
+    }
+
+    // Retourne la porte située sur une case, en la créant au besoin
+    fn door(&mut self, door_at: &mut HashMap<usize, usize>, cell: usize, region: usize) -> usize {
+        *door_at.entry(cell).or_insert_with(|| {
+            let door = self.doors.len();
+            self.doors.push(Door { cell });
+            self.edges.push(vec![]);
+            self.regions[region].doors.push(door);
+            door
+        })
+    }
+
+    // Distances exactes entre les portes d'une même région
+    fn link_doors(&mut self, map: &Map, costs: &CostGrid) {
+        for region in 0..self.regions.len() {
+            let doors = self.regions[region].doors.clone();
+            for &from in &doors {
+                let links: Vec<(usize, f32)> = self.search_region(map, costs, get_neighbors, self.doors[from].cell, |buffers| {
+                    doors
+                        .iter()
+                        .filter(|&&to| to != from)
+                        .map(|&to| (to, buffers.g(self.doors[to].cell)))
+                        .filter(|&(_, cost)| cost.is_finite())
+                        .collect()
+                });
+                self.edges[from].extend(links);
+            }
+        }
+    }
+
+    // Dijkstra limité aux cases de la région de `source`.
+    // `arcs` vaut `get_predecessors` pour calculer les distances vers `source`.
+    fn search_region<'m, I: Iterator<Item = (usize, usize, f32)>, R>(
+        &self,
+        map: &Map,
+        costs: &'m CostGrid,
+        arcs: impl Fn((usize, usize), &'m CostGrid) -> I,
+        source: usize,
+        f: impl FnOnce(&SearchBuffers) -> R,
+    ) -> R {
+        let region = self.region_of[source];
+
+        with_buffers(map.width * map.height, |buffers| {
+            buffers.open_set.push(Node { cost: 0.0, position: source });
+            buffers.set(source, 0.0, None);
+
+            while let Some(Node { position, .. }) = buffers.pop() {
+                if !buffers.close(position) {
+                    continue;
+                }
+
+                let current_g = buffers.g(position);
+                for (nx, ny, move_cost) in arcs(map.position(position), costs) {
+                    let neighbor = map.index(nx, ny);
+                    if self.region_of[neighbor] != region {
+                        continue;
+                    }
+
+                    let tentative_g_score = current_g + move_cost;
+                    if tentative_g_score < buffers.g(neighbor) {
+                        buffers.set(neighbor, tentative_g_score, Some(position));
+                        buffers.open_set.push(Node { cost: tentative_g_score, position: neighbor });
+                    }
+                }
+            }
+
+            f(buffers)
+        })
+    }
+}
+
+// Découpe les cases frontières en entrées continues (8 directions), chacune ordonnée
+fn split_entrances(map: &Map, costs: &CostGrid, cells: &[usize]) -> Vec<Vec<usize>> {
+    let remaining: HashSet<usize> = cells.iter().copied().collect();
+    let mut visited: HashSet<usize> = HashSet::new();
+    let mut entrances = vec![];
+
+    for &cell in cells {
+        if !visited.insert(cell) {
+            continue;
+        }
+        let mut entrance = vec![cell];
+        let mut queue = VecDeque::from([cell]);
+        while let Some(current) = queue.pop_front() {
+            for (nx, ny, _) in get_neighbors(map.position(current), costs) {
+                let neighbor = map.index(nx, ny);
+                if remaining.contains(&neighbor) && visited.insert(neighbor) {
+                    entrance.push(neighbor);
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        entrance.sort_unstable();
+        entrances.push(entrance);
+    }
+
+    entrances
+}
+
+// Recherche hiérarchique : itinéraire de porte en porte, puis affinage case par case (chemin approché)
+pub fn hierarchical_pathfinding(map: &Map, costs: &CostGrid, graph: &RegionGraph, start: (usize, usize), goal: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    info!("🔎 Démarrage de la recherche hiérarchique entre {:?} et {:?}", start, goal);
+
+    // Départ ou arrivée hors de toute région (mur, hors de la carte) : A* les traite comme toute autre requête
+    let (Some(start_region), Some(goal_region)) = (graph.region_at(map, start.0, start.1), graph.region_at(map, goal.0, goal.1)) else {
+        warn!("⚠️ Départ {:?} ou arrivée {:?} hors des régions, recherche directe", start, goal);
+        return astar_pathfinding(map, costs, start, goal);
+    };
+
+    // Trajet court : la recherche directe reste rapide et évite les détours par les portes
+    if heuristic(start, goal) <= (2 * SECTION_SIZE) as f32 {
+        return astar_pathfinding(map, costs, start, goal);
+    }
+
+    let start_index = map.index(start.0, start.1);
+    let goal_index = map.index(goal.0, goal.1);
+    let start_node = graph.doors.len();
+    let goal_node = start_node + 1;
+    let cell_of = |node: usize| match node {
+        n if n == start_node => start_index,
+        n if n == goal_node => goal_index,
+        n => graph.doors[n].cell,
+    };
+
+    // Raccordement du départ et de l'arrivée aux portes de leur région
+    let from_start: Vec<(usize, f32)> = graph.search_region(map, costs, get_neighbors, start_index, |buffers| {
+        let mut links: Vec<(usize, f32)> = graph.regions[start_region]
+            .doors
+            .iter()
+            .map(|&door| (door, buffers.g(graph.doors[door].cell)))
+            .collect();
+        if start_region == goal_region {
+            links.push((goal_node, buffers.g(goal_index)));
+        }
+        links
+    });
+    let to_goal: HashMap<usize, f32> = graph.search_region(map, costs, get_predecessors, goal_index, |buffers| {
+        graph.regions[goal_region]
+            .doors
+            .iter()
+            .map(|&door| (door, buffers.g(graph.doors[door].cell)))
+            .collect()
+    });
+
+    let route = with_buffers(goal_node + 1, |buffers| {
+        buffers.open_set.push(Node { cost: 0.0, position: start_node });
+        buffers.set(start_node, 0.0, None);
+
+        while let Some(Node { position, .. }) = buffers.pop() {
+            if position == goal_node {
+                return Some(buffers.trace(goal_node));
+            }
+            if !buffers.close(position) {
+                continue;
+            }
+
+            let current_g = buffers.g(position);
+            let links = if position == start_node { &from_start[..] } else { &graph.edges[position][..] };
+            let exit = to_goal.get(&position).map(|&cost| (goal_node, cost));
+
+            for &(next, cost) in links.iter().chain(exit.iter()) {
+                let tentative_g_score = current_g + cost;
+                if tentative_g_score < buffers.g(next) {
+                    buffers.set(next, tentative_g_score, Some(position));
+                    buffers.open_set.push(Node {
+                        cost: tentative_g_score + costs.estimate(map.position(cell_of(next)), goal),
+                        position: next,
+                    });
+                }
+            }
+        }
+
+        None
+    });
+
+    let Some(route) = route else {
+        warn!("❌ Aucun chemin trouvé entre {:?} et {:?}", start, goal);
+        return None;
+    };
+
+    // Affinage : A* sur toute la grille entre deux portes d'une même région (jamais plus coûteux
+    // que le trajet interne à la région estimé par le graphe). Budget épuisé : aucun chemin, plutôt
+    // que des morceaux de chemin qui ne se rejoignent pas.
+    let mut cells = vec![start];
+    for pair in route.windows(2) {
+        let (from, to) = (map.position(cell_of(pair[0])), map.position(cell_of(pair[1])));
+        if graph.region_of[cell_of(pair[0])] == graph.region_of[cell_of(pair[1])] {
+            let refined = astar_cells(map, costs, from, to, |position| costs.estimate(position, to))?;
+            cells.extend(refined.into_iter().skip(1));
+        } else {
+            cells.push(to);
+        }
+    }
+
+    info!("✅ Chemin trouvé ({} portes traversées) !", route.len().saturating_sub(2));
+    Some(cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::data::loader::SurfaceType;
+    use crate::navigation::astar::path_cost;
+    use crate::navigation::budget::{with_budget, AbortReason, SearchBudget, SearchLimits};
+    use crate::navigation::testing::{assert_valid_path, sample_pairs, shipped_map, test_profiles};
+
+    // Surcoût toléré par rapport à A* sur la carte livrée
+    const MAX_RATIO: f32 = 1.25;
+
+    #[test]
+    fn cost_stays_close_to_astar_on_shipped_map() {
+        let map = shipped_map();
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            let graph = RegionGraph::build(&map, &costs);
+            for (start, goal) in sample_pairs(&costs, 40, 28) {
+                let expected = astar_pathfinding(&map, &costs, start, goal).map(|cells| path_cost(&costs, &cells));
+                let found = hierarchical_pathfinding(&map, &costs, &graph, start, goal);
+                assert_eq!(expected.is_some(), found.is_some(), "{:?} → {:?}", start, goal);
+                if let (Some(expected), Some(found)) = (expected, found) {
+                    assert_valid_path(&costs, &found, start, goal);
+                    let ratio = path_cost(&costs, &found) / expected.max(f32::EPSILON);
+                    assert!(ratio <= MAX_RATIO, "{:?} → {:?} : {} fois le coût de A*", start, goal, ratio);
+                }
+            }
+        }
+    }
+
+    // Budget épuisé pendant l'affinage : aucun chemin plutôt que des morceaux qui ne se rejoignent pas
+    #[test]
+    fn aborted_search_returns_no_path() {
+        let map = shipped_map();
+        let costs = CostGrid::build(&map, &Default::default());
+        let graph = RegionGraph::build(&map, &costs);
+        let (start, goal) = ((92, 90), (140, 140));
+        let full = hierarchical_pathfinding(&map, &costs, &graph, start, goal).expect("chemin de référence");
+
+        for max_nodes in [10, 200, 1000, 3000] {
+            let budget = SearchBudget::new(SearchLimits { max_nodes: Some(max_nodes), timeout: None });
+            let (cells, aborted) = with_budget(&budget, || hierarchical_pathfinding(&map, &costs, &graph, start, goal));
+            match aborted {
+                Some(reason) => {
+                    assert_eq!(reason, AbortReason::NodeLimit);
+                    assert_eq!(cells, None, "chemin partiel avec {} nœuds", max_nodes);
+                }
+                None => assert_eq!(cells.as_ref(), Some(&full)),
+            }
+        }
+    }
+
+    #[test]
+    fn start_on_wall_behaves_like_astar() {
+        let map = shipped_map();
+        let costs = CostGrid::build(&map, &Default::default());
+        let graph = RegionGraph::build(&map, &costs);
+        let goal = (140, 140);
+        // Mur bordant une zone reliée à l'arrivée, assez loin pour que la recherche passe par les portes
+        let wall = (0..map.height)
+            .flat_map(|y| (0..map.width).map(move |x| (x, y)))
+            .filter(|&(x, y)| map.grid[y][x] == SurfaceType::Mur && get_neighbors((x, y), &costs).next().is_some())
+            .filter(|&wall| heuristic(wall, goal) > (2 * SECTION_SIZE) as f32)
+            .find(|&wall| astar_pathfinding(&map, &costs, wall, goal).is_some())
+            .expect("un mur au bord d'une zone franchissable");
+
+        let expected = astar_pathfinding(&map, &costs, wall, goal);
+        assert_eq!(hierarchical_pathfinding(&map, &costs, &graph, wall, goal), expected);
+    }
+}
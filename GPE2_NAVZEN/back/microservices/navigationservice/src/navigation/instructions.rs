@@ -0,0 +1,283 @@
+// Génération d'instructions de guidage pas à pas à partir des segments d'un chemin.
+//
+// Les segments consécutifs sur une même surface sont regroupés en tronçons tant que
+// la direction ne change pas franchement : les petits crochets d'un chemin de grille
+// ne donnent pas lieu à un virage. Chaque changement de surface (entrée dans une salle,
+// escalier, ascenseur...) et chaque virage devient une étape.
+
+use crate::data::loader::{Map, SurfaceType};
+use crate::navigation::astar::PathSegment;
+use crate::utils::i18n::{Lang, Message};
+use serde::Serialize;
+
+// En dessous de cet angle (degrés), on continue tout droit ; au-dessus de U_TURN_ANGLE, demi-tour
+const STRAIGHT_ANGLE: f32 = 25.0;
+const SLIGHT_ANGLE: f32 = 60.0;
+const U_TURN_ANGLE: f32 = 150.0;
+
+// Un segment plus court que ça (en cases) prolonge le tronçon courant sans créer de virage
+const MIN_TURN_LENGTH: f32 = 2.5;
+
+// Les virages se mesurent par rapport au dernier tronçon d'au moins cette longueur (en cases) :
+// la courte diagonale qui coupe un angle ne transforme pas un virage à droite en deux demi-virages
+const REFERENCE_LENGTH: f32 = 8.0;
+
+// Les tronçons plus courts (passage d'une porte...) ne font pas l'objet d'un "tout droit"
+const MIN_STRAIGHT_METERS: f32 = 1.0;
+
+/// Type de manœuvre, pour afficher une icône par étape côté client
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Maneuver {
+    Depart,
+    Straight,
+    SlightLeft,
+    SlightRight,
+    Left,
+    Right,
+    UTurn,
+    EnterRoom,
+    EnterCorridor,
+    GoOutside,
+    TakeStairs,
+    TakeElevator,
+    Arrive,
+}
+
+/// Étape de guidage : manœuvre, texte affiché et distance à parcourir (en mètres)
+#[derive(Debug, Clone, Serialize)]
+pub struct Instruction {
+    pub maneuver: Maneuver,
+    pub text: String,
+    pub distance_m: f32,
+    pub position: (usize, usize),
+}
+
+// Suite de segments parcourus sur une même surface dans une direction générale
+struct Stretch {
+    start: (usize, usize),
+    end: (usize, usize),
+    surface: SurfaceType,
+    length: f32, // En cases
+}
+
+impl Stretch {
+    fn heading(&self) -> (f32, f32) {
+        heading(self.start, self.end)
+    }
+}
+
+fn heading(from: (usize, usize), to: (usize, usize)) -> (f32, f32) {
+    (to.0 as f32 - from.0 as f32, to.1 as f32 - from.1 as f32)
+}
+
+fn length((dx, dy): (f32, f32)) -> f32 {
+    (dx * dx + dy * dy).sqrt()
+}
+
+// Angle signé (degrés) pour passer d'une direction à l'autre ; positif = vers la droite
+// (l'axe y de la carte est orienté vers le bas)
+fn turn_angle(a: (f32, f32), b: (f32, f32)) -> f32 {
+    let cross = a.0 * b.1 - a.1 * b.0;
+    let dot = a.0 * b.0 + a.1 * b.1;
+    cross.atan2(dot).to_degrees()
+}
+
+fn turn_maneuver(angle: f32) -> Option<Maneuver> {
+    let magnitude = angle.abs();
+    match (magnitude, angle > 0.0) {
+        (m, _) if m < STRAIGHT_ANGLE => None,
+        (m, true) if m < SLIGHT_ANGLE => Some(Maneuver::SlightRight),
+        (m, false) if m < SLIGHT_ANGLE => Some(Maneuver::SlightLeft),
+        (m, _) if m >= U_TURN_ANGLE => Some(Maneuver::UTurn),
+        (_, true) => Some(Maneuver::Right),
+        (_, false) => Some(Maneuver::Left),
+    }
+}
+
+fn surface_maneuver(surface: SurfaceType) -> Maneuver {
+    match surface {
+        SurfaceType::Room(_) => Maneuver::EnterRoom,
+        SurfaceType::Escalier => Maneuver::TakeStairs,
+        SurfaceType::Ascenseur => Maneuver::TakeElevator,
+        SurfaceType::Exterieur => Maneuver::GoOutside,
+        SurfaceType::Couloir | SurfaceType::Mur => Maneuver::EnterCorridor,
+    }
+}
+
+// Texte d'une étape dans la langue demandée
+fn describe(lang: Lang, maneuver: Maneuver, surface: SurfaceType, meters: f32) -> String {
+    let name = || lang.surface_name(surface);
+    let message = match maneuver {
+        Maneuver::Depart => Message::StepDepart(name()),
+        Maneuver::Straight => Message::StepStraight(meters),
+        Maneuver::SlightLeft => Message::StepSlightLeft,
+        Maneuver::SlightRight => Message::StepSlightRight,
+        Maneuver::Left => Message::StepLeft,
+        Maneuver::Right => Message::StepRight,
+        Maneuver::UTurn => Message::StepUTurn,
+        Maneuver::EnterRoom => Message::StepEnterRoom(name()),
+        Maneuver::EnterCorridor => Message::StepEnterCorridor,
+        Maneuver::GoOutside => Message::StepGoOutside,
+        Maneuver::TakeStairs => Message::StepTakeStairs,
+        Maneuver::TakeElevator => Message::StepTakeElevator,
+        Maneuver::Arrive => Message::StepArrive(name()),
+    };
+    lang.message(&message)
+}
+
+// Regroupe les segments en tronçons de même surface et de direction générale constante
+fn stretches(map: &Map, segments: &[PathSegment]) -> Vec<Stretch> {
+    let mut stretches: Vec<Stretch> = vec![];
+
+    for segment in segments {
+        let surface = map.get_surface(segment.end.0, segment.end.1);
+        let direction = heading(segment.start, segment.end);
+        let segment_length = length(direction);
+        if segment_length == 0.0 {
+            continue;
+        }
+
+        if let Some(current) = stretches.last_mut() {
+            let same_surface = current.surface == surface;
+            let keeps_heading = segment_length < MIN_TURN_LENGTH
+                || current.length < MIN_TURN_LENGTH
+                || turn_maneuver(turn_angle(current.heading(), direction)).is_none();
+
+            if same_surface && keeps_heading {
+                current.end = segment.end;
+                current.length += segment_length;
+                continue;
+            }
+        }
+
+        stretches.push(Stretch { start: segment.start, end: segment.end, surface, length: segment_length });
+    }
+
+    stretches
+}
+
+/// Construit les étapes de guidage d'un chemin (départ, tronçons, virages, changements de surface, arrivée)
+pub fn generate_instructions(map: &Map, segments: &[PathSegment], meters_per_cell: f32, lang: Lang) -> Vec<Instruction> {
+    let Some(first) = segments.first() else {
+        return vec![];
+    };
+    let last = &segments[segments.len() - 1];
+
+    let step = |maneuver: Maneuver, surface: SurfaceType, position: (usize, usize), meters: f32| Instruction {
+        maneuver,
+        text: describe(lang, maneuver, surface, meters),
+        distance_m: (meters * 10.0).round() / 10.0,
+        position,
+    };
+
+    let start_surface = map.get_surface(first.start.0, first.start.1);
+    let mut instructions = vec![step(Maneuver::Depart, start_surface, first.start, 0.0)];
+    let mut surface = start_surface;
+    let mut reference: Option<(f32, f32)> = None;
+
+    for stretch in stretches(map, segments) {
+        if stretch.surface != surface {
+            instructions.push(step(surface_maneuver(stretch.surface), stretch.surface, stretch.start, 0.0));
+            surface = stretch.surface;
+        }
+
+        if stretch.length >= REFERENCE_LENGTH {
+            if let Some(turn) = reference.and_then(|heading| turn_maneuver(turn_angle(heading, stretch.heading()))) {
+                instructions.push(step(turn, stretch.surface, stretch.start, 0.0));
+            }
+            reference = Some(stretch.heading());
+        }
+
+        // Sans manœuvre intermédiaire, le tronçon prolonge le "tout droit" précédent
+        let mut meters = stretch.length * meters_per_cell;
+        let mut position = stretch.start;
+        if let Some(last) = instructions.last().filter(|last| last.maneuver == Maneuver::Straight) {
+            meters += last.distance_m;
+            position = last.position;
+            instructions.pop();
+        }
+        if meters >= MIN_STRAIGHT_METERS {
+            instructions.push(step(Maneuver::Straight, stretch.surface, position, meters));
+        }
+    }
+
+    let end_surface = map.get_surface(last.end.0, last.end.1);
+    instructions.push(step(Maneuver::Arrive, end_surface, last.end, 0.0));
+    instructions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::testing::grid;
+
+    // Segments en ligne droite reliant les sommets successifs d'une ligne brisée
+    fn segments(corners: &[(usize, usize)]) -> Vec<PathSegment> {
+        corners
+            .windows(2)
+            .map(|pair| PathSegment { start: pair[0], end: pair[1], surface: String::new(), cost: 0.0, time_s: 0.0, line_number: 0 })
+            .collect()
+    }
+
+    fn maneuvers(instructions: &[Instruction]) -> Vec<Maneuver> {
+        instructions.iter().map(|instruction| instruction.maneuver).collect()
+    }
+
+    #[test]
+    fn turns_are_classified_by_angle() {
+        // L'axe y est orienté vers le bas : passer de l'est au sud, c'est tourner à droite
+        assert!((turn_angle((1.0, 0.0), (0.0, 1.0)) - 90.0).abs() < 1e-3);
+        assert!((turn_angle((1.0, 0.0), (0.0, -1.0)) + 90.0).abs() < 1e-3);
+
+        assert_eq!(turn_maneuver(10.0), None);
+        assert_eq!(turn_maneuver(-20.0), None);
+        assert_eq!(turn_maneuver(40.0), Some(Maneuver::SlightRight));
+        assert_eq!(turn_maneuver(-40.0), Some(Maneuver::SlightLeft));
+        assert_eq!(turn_maneuver(90.0), Some(Maneuver::Right));
+        assert_eq!(turn_maneuver(-90.0), Some(Maneuver::Left));
+        assert_eq!(turn_maneuver(170.0), Some(Maneuver::UTurn));
+        assert_eq!(turn_maneuver(-180.0), Some(Maneuver::UTurn));
+    }
+
+    #[test]
+    fn corners_become_turns() {
+        let map = grid(&[".........."; 10]);
+        let instructions = generate_instructions(&map, &segments(&[(0, 0), (9, 0), (9, 9), (0, 9)]), 0.5, Lang::Fr);
+
+        assert_eq!(
+            maneuvers(&instructions),
+            vec![Maneuver::Depart, Maneuver::Straight, Maneuver::Right, Maneuver::Straight, Maneuver::Right, Maneuver::Straight, Maneuver::Arrive]
+        );
+        assert_eq!(instructions[3].position, (9, 0));
+        assert!(instructions.iter().filter(|step| step.maneuver == Maneuver::Straight).all(|step| step.distance_m == 4.5));
+        assert_eq!(instructions.last().unwrap().position, (0, 9));
+    }
+
+    #[test]
+    fn short_jogs_extend_the_straight_step() {
+        // Le crochet de 3 cases est trop court pour servir de référence : tout droit sur 10 + 3 + 10 cases
+        let map = grid(&["....................."; 4]);
+        let instructions = generate_instructions(&map, &segments(&[(0, 0), (10, 0), (10, 3), (20, 3)]), 0.5, Lang::En);
+
+        assert_eq!(maneuvers(&instructions), vec![Maneuver::Depart, Maneuver::Straight, Maneuver::Arrive]);
+        assert_eq!(instructions[1].distance_m, 11.5);
+        assert_eq!(instructions[1].position, (0, 0));
+        assert_eq!(instructions[1].text, Lang::En.message(&Message::StepStraight(11.5)));
+    }
+
+    #[test]
+    fn surface_changes_split_the_straight_steps() {
+        let map = grid(&["....1111", "....1111"]);
+        let instructions = generate_instructions(&map, &segments(&[(0, 0), (3, 0), (7, 0)]), 1.0, Lang::Fr);
+
+        assert_eq!(
+            maneuvers(&instructions),
+            vec![Maneuver::Depart, Maneuver::Straight, Maneuver::EnterRoom, Maneuver::Straight, Maneuver::Arrive]
+        );
+        assert_eq!(instructions[1].distance_m, 3.0);
+        assert_eq!(instructions[3].distance_m, 4.0);
+        assert_eq!(instructions[2].text, Lang::Fr.message(&Message::StepEnterRoom("Salle 1".to_string())));
+        assert!(generate_instructions(&map, &[], 1.0, Lang::Fr).is_empty());
+    }
+}
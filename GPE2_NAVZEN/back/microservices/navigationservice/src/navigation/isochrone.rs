@@ -0,0 +1,298 @@
+// Zone accessible dans un budget de distance ou de durée (isochrone).
+//
+// Dijkstra vers toutes les cases depuis une ou plusieurs cases de départ : les cases sont
+// développées par coût croissant selon le profil, comme pour un itinéraire, mais le budget
+// porte sur la longueur réelle ou la durée (`TravelTime`) accumulée le long de ces chemins
+// les moins coûteux. Une case n'est donc dans la zone que si l'itinéraire proposé pour
+// l'atteindre tient dans le budget. La zone obtenue peut ensuite être décrite par bandes
+// de cases (masque), par ses contours (polygones) ou par les salles qu'elle touche.
+
+use std::collections::BTreeMap;
+use crate::data::loader::{Map, SurfaceType};
+use crate::navigation::astar::{get_neighbors, step_length};
+use crate::navigation::buffers::{with_buffers, Node};
+use crate::navigation::profile::CostGrid;
+use crate::navigation::travel_time::TravelTime;
+use log::{info, warn};
+use serde::Serialize;
+
+/// Suite de cases accessibles consécutives sur une ligne de la grille
+#[derive(Debug, Clone, Serialize)]
+pub struct Span {
+    pub y: usize,
+    pub x_min: usize,
+    pub x_max: usize,
+}
+
+/// Budget de la zone accessible
+#[derive(Debug, Clone, Copy)]
+pub enum Limit {
+    Length(f32),  // Longueur du chemin, en cases
+    Seconds(f32), // Durée du trajet
+}
+
+/// Case atteinte, avec la longueur (en cases) et la durée du chemin le moins coûteux
+#[derive(Debug, Clone, Copy)]
+pub struct Reached {
+    pub index: usize, // Indice dans la grille aplatie
+    pub length: f32,
+    pub seconds: f32,
+}
+
+/// Cases dont le chemin le moins coûteux tient dans `limit`, par coût croissant
+pub fn reachable_cells(map: &Map, costs: &CostGrid, travel: &TravelTime, sources: &[(usize, usize)], limit: Limit) -> Vec<Reached> {
+    info!("🫧 Zone accessible depuis {} case(s) dans la limite {:?}", sources.len(), limit);
+
+    let reached = cells_within(map, costs, travel, sources, limit);
+    if reached.is_empty() {
+        warn!("❌ Aucune case de départ sur la carte");
+    } else {
+        info!("✅ {} cases accessibles", reached.len());
+    }
+    reached
+}
+
+/// Comme `reachable_cells`, sans journalisation : pour les calculs qui en enchaînent beaucoup
+/// (matrice des distances, une recherche par salle)
+pub(crate) fn cells_within(map: &Map, costs: &CostGrid, travel: &TravelTime, sources: &[(usize, usize)], limit: Limit) -> Vec<Reached> {
+    // Longueur et durée du meilleur chemin connu vers chaque case
+    let mut measures = vec![(0.0f32, 0.0f32); map.width * map.height];
+    with_buffers(map.width * map.height, |buffers| {
+        for &(x, y) in sources.iter().filter(|&&(x, y)| map.contains(x, y)) {
+            let index = map.index(x, y);
+            buffers.open_set.push(Node { cost: 0.0, position: index });
+            buffers.set(index, 0.0, None);
+        }
+
+        let mut reached = vec![];
+        while let Some(Node { position, .. }) = buffers.pop() {
+            if !buffers.close(position) {
+                continue;
+            }
+
+            // Le chemin le moins coûteux est connu : hors budget, la case n'est pas retenue, même si
+            // un chemin plus court mais plus coûteux tiendrait. Elle est tout de même développée :
+            // elle peut offrir le chemin le moins coûteux vers des voisines (qui seront hors budget
+            // elles aussi), sans quoi elles seraient retenues par un chemin plus coûteux.
+            let (length, seconds) = measures[position];
+            let within = match limit {
+                Limit::Length(max) => length <= max,
+                Limit::Seconds(max) => seconds <= max,
+            };
+            if within {
+                reached.push(Reached { index: position, length, seconds });
+            }
+
+            let current = map.position(position);
+            let current_g = buffers.g(position);
+            for (nx, ny, move_cost) in get_neighbors(current, costs) {
+                let neighbor = map.index(nx, ny);
+                let tentative_g_score = current_g + move_cost;
+
+                if tentative_g_score < buffers.g(neighbor) {
+                    let step = step_length(current, (nx, ny));
+                    buffers.set(neighbor, tentative_g_score, Some(position));
+                    measures[neighbor] = (length + step, seconds + travel.step_seconds(map, current, (nx, ny), step));
+                    buffers.open_set.push(Node { cost: tentative_g_score, position: neighbor });
+                }
+            }
+        }
+
+        reached
+    })
+}
+
+// Marque les cases atteintes dans une grille aplatie
+fn inside_mask(map: &Map, reached: &[Reached]) -> Vec<bool> {
+    let mut inside = vec![false; map.width * map.height];
+    for cell in reached {
+        inside[cell.index] = true;
+    }
+    inside
+}
+
+/// Zone accessible ligne par ligne, en bandes de cases consécutives
+pub fn mask_spans(map: &Map, reached: &[Reached]) -> Vec<Span> {
+    let inside = inside_mask(map, reached);
+    let mut spans = vec![];
+
+    for y in 0..map.height {
+        let mut x = 0;
+        while x < map.width {
+            if !inside[map.index(x, y)] {
+                x += 1;
+                continue;
+            }
+            let x_min = x;
+            while x + 1 < map.width && inside[map.index(x + 1, y)] {
+                x += 1;
+            }
+            spans.push(Span { y, x_min, x_max: x });
+            x += 1;
+        }
+    }
+    spans
+}
+
+/// Contours de la zone accessible : anneaux fermés de sommets de la grille (le sommet (x, y)
+/// est le coin supérieur gauche de la case (x, y)), parcourus dans le sens horaire pour un
+/// contour extérieur et antihoraire pour un trou
+pub fn outlines(map: &Map, reached: &[Reached]) -> Vec<Vec<(usize, usize)>> {
+    let inside = inside_mask(map, reached);
+    let is_inside = |x: usize, y: usize, dx: isize, dy: isize| {
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
+        nx >= 0 && ny >= 0 && map.contains(nx as usize, ny as usize) && inside[map.index(nx as usize, ny as usize)]
+    };
+
+    // Arêtes orientées entre une case atteinte et une case qui ne l'est pas, zone à droite
+    let mut edges: BTreeMap<(usize, usize), Vec<(usize, usize)>> = BTreeMap::new();
+    for cell in reached {
+        let (x, y) = map.position(cell.index);
+        let sides = [
+            ((0, -1), (x, y), (x + 1, y)),
+            ((1, 0), (x + 1, y), (x + 1, y + 1)),
+            ((0, 1), (x + 1, y + 1), (x, y + 1)),
+            ((-1, 0), (x, y + 1), (x, y)),
+        ];
+        for ((dx, dy), from, to) in sides {
+            if !is_inside(x, y, dx, dy) {
+                edges.entry(from).or_default().push(to);
+            }
+        }
+    }
+
+    // Chaque sommet a autant d'arêtes entrantes que sortantes : on les enchaîne en anneaux
+    let mut rings = vec![];
+    while let Some((&start, _)) = edges.iter().next() {
+        let mut ring = vec![start];
+        let mut current = start;
+        while let Some(next) = edges.get_mut(&current).and_then(Vec::pop) {
+            if edges.get(&current).is_some_and(Vec::is_empty) {
+                edges.remove(&current);
+            }
+            if next == start {
+                break;
+            }
+            ring.push(next);
+            current = next;
+        }
+        rings.push(without_collinear(ring));
+    }
+    rings
+}
+
+// Retire les sommets situés au milieu d'un côté droit
+fn without_collinear(ring: Vec<(usize, usize)>) -> Vec<(usize, usize)> {
+    let count = ring.len();
+    (0..count)
+        .filter(|&i| {
+            let (prev, point, next) = (ring[(i + count - 1) % count], ring[i], ring[(i + 1) % count]);
+            let turn = (point.0 as isize - prev.0 as isize) * (next.1 as isize - point.1 as isize)
+                - (point.1 as isize - prev.1 as isize) * (next.0 as isize - point.0 as isize);
+            turn != 0
+        })
+        .map(|i| ring[i])
+        .collect()
+}
+
+/// Salles touchées par la zone accessible, avec leur case la moins coûteuse à atteindre
+pub fn reachable_rooms(map: &Map, reached: &[Reached]) -> Vec<(usize, Reached)> {
+    let mut rooms: Vec<(usize, Reached)> = vec![];
+    // Les cases arrivent par coût croissant : la première case d'une salle est la moins coûteuse
+    for cell in reached {
+        let (x, y) = map.position(cell.index);
+        if let SurfaceType::Room(room) = map.get_surface(x, y) {
+            if !rooms.iter().any(|&(known, _)| known == room) {
+                rooms.push((room, *cell));
+            }
+        }
+    }
+    rooms
+}
+
+/// Identifiants de toutes les salles de la carte, par ordre croissant
+pub fn all_rooms(map: &Map) -> Vec<usize> {
+    let mut rooms: Vec<usize> = map
+        .grid
+        .iter()
+        .flatten()
+        .filter_map(|surface| match surface {
+            SurfaceType::Room(room) => Some(*room),
+            _ => None,
+        })
+        .collect();
+    rooms.sort_unstable();
+    rooms.dedup();
+    rooms
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::astar::{astar_pathfinding, reconstruct_path};
+    use crate::navigation::profile::RoutingProfile;
+    use crate::navigation::testing::grid;
+    use crate::utils::i18n::Lang;
+
+    #[test]
+    fn limit_is_measured_along_the_path() {
+        // L'extérieur coûte six fois plus qu'un couloir, mais ne s'y parcourt pas plus lentement
+        let map = grid(&["..oooo.1"]);
+        let profile = RoutingProfile { exterieur: Some(6.0), ..RoutingProfile::default() };
+        let costs = CostGrid::build(&map, &profile);
+        let travel = TravelTime::new(&profile, 0.5);
+
+        let reached = reachable_cells(&map, &costs, &travel, &[(0, 0)], Limit::Length(5.0));
+        assert_eq!(reached.iter().map(|cell| cell.index).collect::<Vec<_>>(), vec![0, 1, 2, 3, 4, 5]);
+        assert!(reached.iter().all(|cell| cell.length == cell.index as f32));
+
+        // La durée de la salle est celle des segments de l'itinéraire
+        let cells: Vec<(usize, usize)> = (0..8).map(|x| (x, 0)).collect();
+        let duration: f32 = reconstruct_path(&map, &costs, &travel, &cells, Lang::Fr).iter().map(|segment| segment.time_s).sum();
+        let reached = reachable_cells(&map, &costs, &travel, &[(0, 0)], Limit::Seconds(duration + 0.01));
+        let rooms = reachable_rooms(&map, &reached);
+        assert_eq!(rooms.len(), 1);
+        assert!((rooms[0].1.seconds - duration).abs() < 1e-3);
+        assert_eq!(rooms[0].1.length, 7.0);
+
+        let reached = reachable_cells(&map, &costs, &travel, &[(0, 0)], Limit::Seconds(duration - 0.01));
+        assert!(reachable_rooms(&map, &reached).is_empty());
+    }
+
+    #[test]
+    fn cheapest_route_decides_membership() {
+        // Le détour par le couloir (environ 16 cases) est moins coûteux que le raccourci
+        // extérieur (10 cases) : avec un budget de 12 cases, la porte (10, 0) est hors zone
+        let map = grid(&[
+            ".oooooooo..1",
+            ".########.##",
+            ".########.##",
+            ".########.##",
+            "..........##",
+        ]);
+        let profile = RoutingProfile { exterieur: Some(6.0), ..RoutingProfile::default() };
+        let costs = CostGrid::build(&map, &profile);
+        let travel = TravelTime::new(&profile, 0.5);
+        let length = |cells: &[(usize, usize)]| -> f32 { cells.windows(2).map(|pair| step_length(pair[0], pair[1])).sum() };
+
+        let reached = reachable_cells(&map, &costs, &travel, &[(0, 0)], Limit::Length(12.0));
+        let door = astar_pathfinding(&map, &costs, (0, 0), (10, 0)).unwrap();
+        assert!(length(&door) > 12.0 && door.contains(&(5, 4)));
+        assert!(reached.iter().all(|cell| cell.index != map.index(10, 0)), "la porte est atteinte par un chemin plus coûteux");
+        assert!(reached.iter().any(|cell| cell.index == map.index(3, 0)));
+        assert!(reached.iter().any(|cell| cell.index == map.index(5, 4)));
+
+        // Chaque case retenue l'est avec la longueur de son chemin le moins coûteux, dans le budget
+        for cell in &reached {
+            let cells = astar_pathfinding(&map, &costs, (0, 0), map.position(cell.index)).unwrap();
+            assert!((length(&cells) - cell.length).abs() < 1e-3);
+            assert!(cell.length <= 12.0);
+        }
+
+        // Avec un budget suffisant, la porte et la salle sont atteintes par le détour
+        let reached = reachable_cells(&map, &costs, &travel, &[(0, 0)], Limit::Length(length(&door) + 1.0));
+        let rooms = reachable_rooms(&map, &reached);
+        assert_eq!(rooms.len(), 1);
+        assert!((rooms[0].1.length - length(&door) - 1.0).abs() < 1e-3);
+    }
+}
@@ -0,0 +1,308 @@
+// Jump Point Search (JPS) étendu aux surfaces pondérées.
+//
+// Le JPS classique n'est correct que sur une grille à coût uniforme : il élague
+// les voisins atteignables aussi bien sans passer par la case courante, en ne
+// regardant que le voisinage 3x3 de cette case. On conserve exactement ces règles
+// (version avec coupe des coins), mais uniquement sur les cases "uniformes",
+// c'est-à-dire dont les voisins sont soit infranchissables, soit du même multiplicateur
+// de coût qu'elles pour le profil choisi et atteignables par un pas autorisé (pas de
+// diagonale interdite entre deux murs autour d'une case uniforme).
+//
+// Pourquoi le coût reste identique à celui de `astar_pathfinding` :
+// - sur une case uniforme, tous les chemins alternatifs utilisés par la preuve
+//   d'élagage restent dans le voisinage 3x3, donc tous leurs coûts sont multipliés
+//   par le même facteur : l'argument du JPS uniforme s'applique tel quel ;
+// - une case non uniforme est toujours un point de saut et elle est développée
+//   sans aucun élagage, d'un seul pas dans les 8 directions, comme dans A* ;
+// - le coût d'un saut est la somme exacte des coûts de chaque pas ;
+// - les deux recherches utilisent la même heuristique octile cohérente, elles
+//   retournent donc toutes deux un chemin de coût optimal.
+//
+// Avec une pénalité de proximité des murs, toute la bande qui longe les murs est non
+// uniforme : JPS reste exact mais perd l'essentiel de son avance sur A* (et ALT).
+
+use crate::data::loader::Map;
+use crate::navigation::astar::MOVES;
+use crate::navigation::buffers::{with_buffers, Node};
+use crate::navigation::profile::CostGrid;
+use log::{info, warn};
+
+// Coût de base d'un pas dans une direction
+fn step_cost(direction: (isize, isize)) -> f32 {
+    if direction.0 != 0 && direction.1 != 0 { 1.4 } else { 1.0 }
+}
+
+// Multiplicateur de la case (x, y), None si infranchissable ou hors de la carte
+fn multiplier_at(costs: &CostGrid, x: isize, y: isize) -> Option<f32> {
+    if x < 0 || y < 0 {
+        return None;
+    }
+    costs.multiplier(x as usize, y as usize)
+}
+
+fn is_blocked(costs: &CostGrid, x: isize, y: isize) -> bool {
+    multiplier_at(costs, x, y).is_none()
+}
+
+// Pas autorisé entre deux cases voisines franchissables (voir `CostGrid::allows_move`)
+fn allows_move(costs: &CostGrid, (x, y): (isize, isize), (nx, ny): (isize, isize)) -> bool {
+    costs.allows_move((x as usize, y as usize), (nx as usize, ny as usize))
+}
+
+// Une case est uniforme si tous ses voisins franchissables ont le même multiplicateur qu'elle
+// et sont atteignables directement
+fn is_uniform(costs: &CostGrid, x: isize, y: isize) -> bool {
+    let Some(own) = multiplier_at(costs, x, y) else {
+        return false;
+    };
+    MOVES.iter().all(|&(dx, dy, _)| match multiplier_at(costs, x + dx, y + dy) {
+        Some(other) => other == own && allows_move(costs, (x, y), (x + dx, y + dy)),
+        None => true,
+    })
+}
+
+// Présence d'un voisin forcé en arrivant sur (x, y) avec la direction donnée
+fn has_forced_neighbor(costs: &CostGrid, x: isize, y: isize, (dx, dy): (isize, isize)) -> bool {
+    if dx != 0 && dy != 0 {
+        (is_blocked(costs, x - dx, y) && !is_blocked(costs, x - dx, y + dy))
+            || (is_blocked(costs, x, y - dy) && !is_blocked(costs, x + dx, y - dy))
+    } else if dx != 0 {
+        (is_blocked(costs, x, y + 1) && !is_blocked(costs, x + dx, y + 1))
+            || (is_blocked(costs, x, y - 1) && !is_blocked(costs, x + dx, y - 1))
+    } else {
+        (is_blocked(costs, x + 1, y) && !is_blocked(costs, x + 1, y + dy))
+            || (is_blocked(costs, x - 1, y) && !is_blocked(costs, x - 1, y + dy))
+    }
+}
+
+// Avance d'une seule case depuis (x, y) ; utilisé depuis les cases non uniformes
+fn step(costs: &CostGrid, (x, y): (isize, isize), direction: (isize, isize), g: f32) -> Option<((isize, isize), f32)> {
+    let next = (x + direction.0, y + direction.1);
+    let multiplier = multiplier_at(costs, next.0, next.1)?;
+    allows_move(costs, (x, y), next).then(|| (next, g + step_cost(direction) * multiplier))
+}
+
+// Avance depuis (x, y) dans une direction jusqu'au prochain point de saut.
+// Retourne la position atteinte et le coût cumulé depuis `g`.
+fn jump(costs: &CostGrid, from: (isize, isize), direction: (isize, isize), goal: (isize, isize), mut g: f32) -> Option<((isize, isize), f32)> {
+    let (dx, dy) = direction;
+    let (mut x, mut y) = from;
+
+    loop {
+        let previous = (x, y);
+        x += dx;
+        y += dy;
+
+        let multiplier = multiplier_at(costs, x, y)?;
+        if !allows_move(costs, previous, (x, y)) {
+            return None;
+        }
+        g += step_cost(direction) * multiplier;
+
+        if (x, y) == goal || !is_uniform(costs, x, y) || has_forced_neighbor(costs, x, y, direction) {
+            return Some(((x, y), g));
+        }
+
+        // En diagonale, la case est un point de saut si un saut droit en part avec succès
+        if dx != 0 && dy != 0
+            && (jump(costs, (x, y), (dx, 0), goal, g).is_some() || jump(costs, (x, y), (0, dy), goal, g).is_some())
+        {
+            return Some(((x, y), g));
+        }
+    }
+}
+
+// Directions à explorer depuis une case (au plus 8), selon la direction d'arrivée
+struct Directions {
+    items: [(isize, isize); 8],
+    len: usize,
+}
+
+impl Directions {
+    fn push(&mut self, direction: (isize, isize)) {
+        self.items[self.len] = direction;
+        self.len += 1;
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (isize, isize)> + '_ {
+        self.items[..self.len].iter().copied()
+    }
+}
+
+// Directions à explorer depuis une case, selon la direction d'arrivée (sans allocation)
+fn successor_directions(costs: &CostGrid, (x, y): (isize, isize), parent: Option<(isize, isize)>) -> Directions {
+    let direction = parent.map(|(px, py)| ((x - px).signum(), (y - py).signum()));
+
+    let (dx, dy) = match direction {
+        Some(direction) if is_uniform(costs, x, y) => direction,
+        // Départ ou case non uniforme : aucun élagage
+        _ => return Directions { items: MOVES.map(|(dx, dy, _)| (dx, dy)), len: MOVES.len() },
+    };
+
+    let mut directions = Directions { items: [(0, 0); 8], len: 0 };
+    if dx != 0 && dy != 0 {
+        directions.push((dx, 0));
+        directions.push((0, dy));
+        directions.push((dx, dy));
+        if is_blocked(costs, x - dx, y) {
+            directions.push((-dx, dy));
+        }
+        if is_blocked(costs, x, y - dy) {
+            directions.push((dx, -dy));
+        }
+    } else if dx != 0 {
+        directions.push((dx, 0));
+        for side in [1, -1] {
+            if is_blocked(costs, x, y + side) {
+                directions.push((dx, side));
+            }
+        }
+    } else {
+        directions.push((0, dy));
+        for side in [1, -1] {
+            if is_blocked(costs, x + side, y) {
+                directions.push((side, dy));
+            }
+        }
+    }
+    directions
+}
+
+// Recherche JPS, alternative à `astar_pathfinding` retournant un chemin de même coût
+pub fn jps_pathfinding(map: &Map, costs: &CostGrid, start: (usize, usize), goal: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    info!("🔎 Démarrage de JPS entre {:?} et {:?}", start, goal);
+
+    if !map.contains(start.0, start.1) || !map.contains(goal.0, goal.1) {
+        warn!("❌ Départ {:?} ou arrivée {:?} hors de la carte", start, goal);
+        return None;
+    }
+
+    let start_index = map.index(start.0, start.1);
+    let goal_index = map.index(goal.0, goal.1);
+    let target = (goal.0 as isize, goal.1 as isize);
+
+    let jump_points = with_buffers(map.width * map.height, |buffers| {
+        buffers.open_set.push(Node { cost: 0.0, position: start_index });
+        buffers.set(start_index, 0.0, None);
+
+        while let Some(Node { position, .. }) = buffers.pop() {
+            if position == goal_index {
+                return Some(buffers.trace(goal_index));
+            }
+
+            if !buffers.close(position) {
+                continue;
+            }
+
+            let (cx, cy) = map.position(position);
+            let current = (cx as isize, cy as isize);
+            let parent = buffers.parent(position).map(|p| {
+                let (px, py) = map.position(p);
+                (px as isize, py as isize)
+            });
+            let current_g = buffers.g(position);
+            let uniform = is_uniform(costs, current.0, current.1);
+
+            for direction in successor_directions(costs, current, parent).iter() {
+                let successor = if uniform {
+                    jump(costs, current, direction, target, current_g)
+                } else {
+                    step(costs, current, direction, current_g)
+                };
+                let Some(((jx, jy), g)) = successor else {
+                    continue;
+                };
+
+                let jump_point = map.index(jx as usize, jy as usize);
+                if g < buffers.g(jump_point) {
+                    buffers.set(jump_point, g, Some(position));
+                    buffers.open_set.push(Node {
+                        cost: g + costs.estimate((jx as usize, jy as usize), goal),
+                        position: jump_point,
+                    });
+                }
+            }
+        }
+
+        None
+    });
+
+    match jump_points {
+        Some(jump_points) => {
+            info!("✅ Chemin trouvé ({} points de saut) !", jump_points.len());
+            Some(expand_jump_points(map, &jump_points))
+        }
+        None => {
+            warn!("❌ Aucun chemin trouvé entre {:?} et {:?}", start, goal);
+            None
+        }
+    }
+}
+
+// Reconstitue toutes les cases du chemin entre les points de saut (lignes droites ou diagonales)
+fn expand_jump_points(map: &Map, jump_points: &[usize]) -> Vec<(usize, usize)> {
+    let mut cells = vec![map.position(jump_points[0])];
+
+    for pair in jump_points.windows(2) {
+        let (mut x, mut y) = map.position(pair[0]);
+        let (tx, ty) = map.position(pair[1]);
+
+        while (x, y) != (tx, ty) {
+            x = (x as isize + (tx as isize - x as isize).signum()) as usize;
+            y = (y as isize + (ty as isize - y as isize).signum()) as usize;
+            cells.push((x, y));
+        }
+    }
+
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::profile::RoutingProfile;
+    use crate::navigation::testing::{assert_matches_astar, grid, sample_pairs, shipped_map, test_profiles};
+
+    #[test]
+    fn same_cost_as_astar_on_shipped_map() {
+        let map = shipped_map();
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            for (start, goal) in sample_pairs(&costs, 25, 27) {
+                assert_matches_astar(&map, &costs, start, goal, jps_pathfinding(&map, &costs, start, goal));
+            }
+        }
+    }
+
+    // Surfaces pondérées mélangées, passages en diagonale entre deux murs et couloirs étroits
+    #[test]
+    fn same_cost_as_astar_on_weighted_and_squeeze_grids() {
+        let map = grid(&[
+            "..........oooo..",
+            ".####.##..oooo..",
+            ".#..#.#..#....#.",
+            ".#..#..#.#.EE.#.",
+            "....##..#.....#.",
+            "#.#..#.#..AA..1.",
+            ".#.#...#.####.1.",
+            "..#..#.....oo.11",
+            ".#..#.#.#..oo...",
+            "...#...#...##.#.",
+        ]);
+        let profiles = test_profiles().into_iter().chain([RoutingProfile {
+            couloir: Some(1.0),
+            salle: Some(3.0),
+            escalier: Some(0.5),
+            ascenseur: Some(4.0),
+            exterieur: Some(2.5),
+            ..RoutingProfile::default()
+        }]);
+
+        for profile in profiles {
+            let costs = CostGrid::build(&map, &profile);
+            for (start, goal) in sample_pairs(&costs, 80, 7) {
+                assert_matches_astar(&map, &costs, start, goal, jps_pathfinding(&map, &costs, start, goal));
+            }
+        }
+    }
+}
@@ -0,0 +1,171 @@
+// Heuristique ALT (A*, Landmarks, Triangle inequality).
+//
+// Prétraitement optionnel : on choisit des cases repères, puis on stocke la distance
+// exacte de chaque repère vers toutes les cases et de toutes les cases vers chaque repère
+// (les coûts dépendent de la case d'arrivée, le graphe n'est donc pas symétrique).
+// Pour une case v, un repère L et l'arrivée t, l'inégalité triangulaire donne :
+//   d(v, t) >= d(L, t) - d(L, v)   et   d(v, t) >= d(v, L) - d(t, L)
+// Ces bornes sont exactes vis-à-vis des vrais coûts (Escalier, Ascenseur, Exterieur),
+// l'heuristique reste donc admissible quels que soient les multiplicateurs. Les
+// distances dépendent du profil : les repères sont précalculés pour chacun d'eux.
+
+use crate::data::loader::Map;
+use crate::navigation::astar::{astar_cells, get_neighbors, get_predecessors, report_outcome};
+use crate::navigation::buffers::{with_buffers, Node};
+use crate::navigation::profile::CostGrid;
+use log::info;
+use rayon::prelude::*;
+
+pub struct Landmarks {
+    cells: Vec<usize>,
+    from_landmark: Vec<Vec<f32>>, // from_landmark[l][v] = d(repère l, v)
+    to_landmark: Vec<Vec<f32>>,   // to_landmark[l][v] = d(v, repère l)
+}
+
+impl Landmarks {
+    /// Choisit `count` repères par sélection des cases les plus éloignées et précalcule leurs distances
+    pub fn build(map: &Map, costs: &CostGrid, count: usize) -> Self {
+        let mut landmarks = Self { cells: vec![], from_landmark: vec![], to_landmark: vec![] };
+
+        let Some(first_walkable) = (0..map.width * map.height).find(|&i| {
+            let (x, y) = map.position(i);
+            costs.multiplier(x, y).is_some()
+        }) else {
+            return landmarks;
+        };
+
+        // Le premier repère est la case la plus éloignée d'une case quelconque,
+        // les suivants maximisent la distance au repère le plus proche
+        let mut nearest = shortest_distances(map, costs, first_walkable, false);
+        for _ in 0..count {
+            let candidate = nearest
+                .iter()
+                .enumerate()
+                .filter(|(_, d)| d.is_finite())
+                .max_by(|a, b| a.1.total_cmp(b.1))
+                .map(|(i, _)| i);
+
+            match candidate {
+                Some(cell) if !landmarks.cells.contains(&cell) => {
+                    let distances = shortest_distances(map, costs, cell, false);
+                    for (n, d) in nearest.iter_mut().zip(&distances) {
+                        *n = if landmarks.cells.is_empty() { *d } else { n.min(*d) };
+                    }
+                    landmarks.cells.push(cell);
+                    landmarks.from_landmark.push(distances);
+                }
+                _ => break,
+            }
+        }
+
+        // Distances vers chaque repère, calculées en parallèle
+        landmarks.to_landmark = landmarks
+            .cells
+            .par_iter()
+            .map(|&cell| shortest_distances(map, costs, cell, true))
+            .collect();
+
+        info!("📍 {} repères ALT précalculés", landmarks.cells.len());
+        landmarks
+    }
+
+    /// Borne inférieure du coût de `v` à `t` (indices de la grille aplatie)
+    pub fn lower_bound(&self, v: usize, t: usize) -> f32 {
+        let mut bound: f32 = 0.0;
+        for (from, to) in self.from_landmark.iter().zip(&self.to_landmark) {
+            let forward = from[t] - from[v];
+            if forward.is_finite() {
+                bound = bound.max(forward);
+            }
+            let backward = to[v] - to[t];
+            if backward.is_finite() {
+                bound = bound.max(backward);
+            }
+        }
+        bound
+    }
+}
+
+// Dijkstra complet depuis une case ; `reverse` calcule les distances de chaque case vers `source`
+fn shortest_distances(map: &Map, costs: &CostGrid, source: usize, reverse: bool) -> Vec<f32> {
+    let size = map.width * map.height;
+
+    with_buffers(size, |buffers| {
+        buffers.open_set.push(Node { cost: 0.0, position: source });
+        buffers.set(source, 0.0, None);
+
+        while let Some(Node { position, .. }) = buffers.pop() {
+            if !buffers.close(position) {
+                continue;
+            }
+
+            let current = map.position(position);
+            let current_g = buffers.g(position);
+            let arcs: Vec<(usize, usize, f32)> = if reverse {
+                get_predecessors(current, costs).collect()
+            } else {
+                get_neighbors(current, costs).collect()
+            };
+
+            for (nx, ny, cost) in arcs {
+                let neighbor = map.index(nx, ny);
+                let tentative_g_score = current_g + cost;
+                if tentative_g_score < buffers.g(neighbor) {
+                    buffers.set(neighbor, tentative_g_score, Some(position));
+                    buffers.open_set.push(Node { cost: tentative_g_score, position: neighbor });
+                }
+            }
+        }
+
+        (0..size).map(|i| buffers.g(i)).collect()
+    })
+}
+
+// A* guidé par l'heuristique ALT (combinée à l'heuristique octile)
+pub fn alt_pathfinding(map: &Map, costs: &CostGrid, landmarks: &Landmarks, start: (usize, usize), goal: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    info!("🔎 Démarrage de A* (ALT) entre {:?} et {:?}", start, goal);
+
+    let target = map.index(goal.0, goal.1);
+    let cells = astar_cells(map, costs, start, goal, |position| {
+        let bound = landmarks.lower_bound(map.index(position.0, position.1), target);
+        bound.max(costs.estimate(position, goal))
+    });
+    report_outcome(start, goal, cells)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::testing::{assert_matches_astar, grid, sample_pairs, shipped_map, test_profiles};
+
+    #[test]
+    fn same_cost_as_astar_on_shipped_map() {
+        let map = shipped_map();
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            let landmarks = Landmarks::build(&map, &costs, 4);
+            for (start, goal) in sample_pairs(&costs, 25, 29) {
+                assert_matches_astar(&map, &costs, start, goal, alt_pathfinding(&map, &costs, &landmarks, start, goal));
+            }
+        }
+    }
+
+    // Deux zones séparées : les distances infinies des repères ne doivent pas fausser la borne
+    #[test]
+    fn same_cost_as_astar_across_disconnected_areas() {
+        let map = grid(&[
+            "......#.oo",
+            ".####.#.oo",
+            "...E..#.AA",
+            "##.##.####",
+            "1111......",
+        ]);
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            let landmarks = Landmarks::build(&map, &costs, 3);
+            for (start, goal) in sample_pairs(&costs, 60, 9) {
+                assert_matches_astar(&map, &costs, start, goal, alt_pathfinding(&map, &costs, &landmarks, start, goal));
+            }
+        }
+    }
+}
@@ -1,2 +1,21 @@
+pub mod alternatives;
 pub mod astar;
+pub mod bidirectional;
+pub mod budget;
+pub mod buffers;
+pub mod distances;
+pub mod evacuation;
+pub mod hierarchy;
+pub mod instructions;
+pub mod isochrone;
+pub mod jps;
+pub mod landmarks;
 pub mod localization;
+pub mod nearest;
+pub mod profile;
+pub mod smoothing;
+#[cfg(test)]
+pub(crate) mod testing;
+pub mod timed;
+pub mod tour;
+pub mod travel_time;
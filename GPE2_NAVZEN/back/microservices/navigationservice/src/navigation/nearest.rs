@@ -0,0 +1,219 @@
+// Recherche du lieu le plus proche d'un type donné (sortie, ascenseur, salle...).
+//
+// Dijkstra à cibles multiples : on développe depuis la position de l'utilisateur
+// jusqu'à fermer la première case qui correspond au type demandé. Comme les cases
+// sont fermées par coût croissant, c'est la cible la moins coûteuse à atteindre
+// selon le profil, pas forcément la plus proche à vol d'oiseau. Avec plusieurs cases
+// de départ, la même recherche relie deux salles par leurs portes les mieux placées.
+
+use crate::data::loader::{Map, SurfaceType};
+use crate::navigation::astar::{get_neighbors, MOVES};
+use crate::navigation::buffers::{with_buffers, Node};
+use crate::navigation::profile::CostGrid;
+use log::{info, warn};
+
+/// Type de lieu recherché par `/navigate/nearest`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Facility {
+    Surface(SurfaceType), // Une surface précise (couloir, escalier, ascenseur, extérieur)
+    AnyRoom,              // N'importe quelle salle
+    Exit,                 // Case extérieure au seuil du bâtiment
+}
+
+impl Facility {
+    /// Type correspondant au paramètre `type`, en français ou en anglais
+    pub fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "couloir" | "corridor" => Some(Facility::Surface(SurfaceType::Couloir)),
+            "escalier" | "stairs" => Some(Facility::Surface(SurfaceType::Escalier)),
+            "ascenseur" | "elevator" => Some(Facility::Surface(SurfaceType::Ascenseur)),
+            "exterieur" | "outdoors" => Some(Facility::Surface(SurfaceType::Exterieur)),
+            "salle" | "room" => Some(Facility::AnyRoom),
+            "sortie" | "exit" => Some(Facility::Exit),
+            _ => None,
+        }
+    }
+
+    /// Indique si la case (x, y) est un lieu de ce type
+    pub fn matches(self, map: &Map, (x, y): (usize, usize)) -> bool {
+        let surface = map.get_surface(x, y);
+        match self {
+            Facility::Surface(expected) => surface == expected,
+            Facility::AnyRoom => matches!(surface, SurfaceType::Room(_)),
+            Facility::Exit => surface == SurfaceType::Exterieur && touches_building(map, (x, y)),
+        }
+    }
+}
+
+// Une case extérieure voisine d'une case praticable du bâtiment est une sortie
+fn touches_building(map: &Map, (x, y): (usize, usize)) -> bool {
+    MOVES.iter().any(|&(dx, dy, _)| {
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
+        nx >= 0
+            && ny >= 0
+            && map.contains(nx as usize, ny as usize)
+            && !matches!(map.get_surface(nx as usize, ny as usize), SurfaceType::Exterieur | SurfaceType::Mur)
+    })
+}
+
+/// Chemin vers la case la moins coûteuse à atteindre parmi celles de `is_target`, en partant
+/// de n'importe quelle case de `sources` (toutes à coût nul)
+pub fn nearest_cells(
+    map: &Map,
+    costs: &CostGrid,
+    sources: &[(usize, usize)],
+    is_target: impl Fn((usize, usize)) -> bool,
+) -> Option<Vec<(usize, usize)>> {
+    if let Some(outside) = sources.iter().find(|&&(x, y)| !map.contains(x, y)) {
+        warn!("❌ Départ {:?} hors de la carte", outside);
+        return None;
+    }
+
+    let cells = with_buffers(map.width * map.height, |buffers| {
+        for &(x, y) in sources {
+            let index = map.index(x, y);
+            buffers.open_set.push(Node { cost: 0.0, position: index });
+            buffers.set(index, 0.0, None);
+        }
+
+        while let Some(Node { position, .. }) = buffers.pop() {
+            if !buffers.close(position) {
+                continue;
+            }
+
+            let current = map.position(position);
+            if is_target(current) {
+                return Some(buffers.trace(position));
+            }
+
+            let current_g = buffers.g(position);
+            for (nx, ny, move_cost) in get_neighbors(current, costs) {
+                let neighbor = map.index(nx, ny);
+                let tentative_g_score = current_g + move_cost;
+
+                if tentative_g_score < buffers.g(neighbor) {
+                    buffers.set(neighbor, tentative_g_score, Some(position));
+                    buffers.open_set.push(Node { cost: tentative_g_score, position: neighbor });
+                }
+            }
+        }
+
+        None
+    })?;
+
+    Some(cells.into_iter().map(|i| map.position(i)).collect())
+}
+
+// Dijkstra à cibles multiples vers le lieu du type demandé le plus proche
+pub fn nearest_pathfinding(map: &Map, costs: &CostGrid, start: (usize, usize), facility: Facility) -> Option<Vec<(usize, usize)>> {
+    info!("🔎 Recherche du lieu le plus proche ({:?}) depuis {:?}", facility, start);
+
+    match nearest_cells(map, costs, &[start], |cell| facility.matches(map, cell)) {
+        Some(cells) => {
+            info!("✅ Lieu trouvé en {:?} ({} cases) !", cells[cells.len() - 1], cells.len());
+            Some(cells)
+        }
+        None => {
+            warn!("❌ Aucun lieu {:?} accessible depuis {:?}", facility, start);
+            None
+        }
+    }
+}
+
+// Dijkstra à sources et cibles multiples : relie au moindre coût deux zones (salles...)
+pub fn area_pathfinding(
+    map: &Map,
+    costs: &CostGrid,
+    sources: &[(usize, usize)],
+    targets: &[(usize, usize)],
+) -> Option<Vec<(usize, usize)>> {
+    info!("🔎 Recherche entre zones : {} cases de départ, {} cases d'arrivée", sources.len(), targets.len());
+
+    let mut is_target = vec![false; map.width * map.height];
+    for &(x, y) in targets.iter().filter(|&&(x, y)| map.contains(x, y)) {
+        is_target[map.index(x, y)] = true;
+    }
+
+    match nearest_cells(map, costs, sources, |(x, y)| is_target[map.index(x, y)]) {
+        Some(cells) => {
+            info!("✅ Chemin trouvé de {:?} à {:?} ({} cases) !", cells[0], cells[cells.len() - 1], cells.len());
+            Some(cells)
+        }
+        None => {
+            warn!("❌ Aucun chemin trouvé entre les zones demandées");
+            None
+        }
+    }
+}
+
+/// Chemins les moins coûteux de `source` vers chacune des `targets` (None si inaccessible), en un
+/// seul Dijkstra arrêté dès que toutes les cibles sont atteintes
+pub fn paths_to_targets(map: &Map, costs: &CostGrid, source: (usize, usize), targets: &[(usize, usize)]) -> Vec<Option<Vec<(usize, usize)>>> {
+    if !map.contains(source.0, source.1) {
+        warn!("❌ Départ {:?} hors de la carte", source);
+        return vec![None; targets.len()];
+    }
+
+    let indices: Vec<Option<usize>> = targets.iter().map(|&(x, y)| map.contains(x, y).then(|| map.index(x, y))).collect();
+    let mut is_target = vec![false; map.width * map.height];
+    for index in indices.iter().flatten() {
+        is_target[*index] = true;
+    }
+    let mut remaining = is_target.iter().filter(|&&target| target).count();
+
+    with_buffers(map.width * map.height, |buffers| {
+        let start = map.index(source.0, source.1);
+        buffers.open_set.push(Node { cost: 0.0, position: start });
+        buffers.set(start, 0.0, None);
+
+        while let Some(Node { position, .. }) = buffers.pop() {
+            if !buffers.close(position) {
+                continue;
+            }
+            if is_target[position] {
+                remaining -= 1;
+                if remaining == 0 {
+                    break;
+                }
+            }
+
+            let current_g = buffers.g(position);
+            for (nx, ny, move_cost) in get_neighbors(map.position(position), costs) {
+                let neighbor = map.index(nx, ny);
+                let tentative_g_score = current_g + move_cost;
+                if tentative_g_score < buffers.g(neighbor) {
+                    buffers.set(neighbor, tentative_g_score, Some(position));
+                    buffers.open_set.push(Node { cost: tentative_g_score, position: neighbor });
+                }
+            }
+        }
+
+        // Seules les cibles fermées ont un coût définitif (les autres sont inaccessibles, ou le budget est épuisé)
+        indices
+            .iter()
+            .map(|index| {
+                let index = index.filter(|&index| buffers.is_closed(index))?;
+                Some(buffers.trace(index).into_iter().map(|i| map.position(i)).collect())
+            })
+            .collect()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::testing::{assert_matches_astar, sample_cells, shipped_map, test_profiles};
+
+    #[test]
+    fn paths_to_targets_match_astar() {
+        let map = shipped_map();
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            let cells = sample_cells(&costs, 8, 38);
+            let (source, targets) = (cells[0], &cells[1..]);
+            for (&target, found) in targets.iter().zip(paths_to_targets(&map, &costs, source, targets)) {
+                assert_matches_astar(&map, &costs, source, target, found);
+            }
+        }
+    }
+}
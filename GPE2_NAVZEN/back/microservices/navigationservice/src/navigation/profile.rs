@@ -0,0 +1,347 @@
+use std::collections::BTreeMap;
+use std::fs;
+use crate::data::loader::{Map, SurfaceType};
+use crate::navigation::astar::heuristic;
+use log::{error, info, warn};
+use serde::Deserialize;
+
+// Nom du profil utilisé quand le client n'en précise pas
+pub const DEFAULT_PROFILE: &str = "default";
+
+// Distance aux murs (en cases) au-delà de laquelle une case n'est plus pénalisée
+const CLEARANCE_RANGE: f32 = 4.0;
+
+fn walkable() -> Option<f32> { Some(1.0) }
+fn floor_change() -> Option<f32> { Some(2.0) }
+fn outdoor() -> Option<f32> { Some(1.5) }
+fn neutral() -> f32 { 1.0 }
+fn allowed() -> bool { true }
+fn walking_speed() -> f32 { 1.3 }
+fn stairs_slowdown() -> f32 { 2.0 }
+fn elevator_wait() -> f32 { 30.0 }
+
+/// Profil d'itinéraire : multiplicateur de coût par surface (`null` = surface interdite) et
+/// vitesses servant à estimer les durées de parcours.
+///
+/// Les champs absents reprennent les coûts standards de la carte et une marche moyenne.
+#[derive(Debug, Clone, Deserialize)]
+pub struct RoutingProfile {
+    #[serde(default = "walkable")]
+    pub couloir: Option<f32>,
+    #[serde(default = "walkable")]
+    pub salle: Option<f32>,
+    #[serde(default = "floor_change")]
+    pub escalier: Option<f32>,
+    #[serde(default = "floor_change")]
+    pub ascenseur: Option<f32>,
+    #[serde(default = "outdoor")]
+    pub exterieur: Option<f32>,
+    #[serde(default = "neutral")]
+    pub couloir_etroit: f32, // Multiplicateur supplémentaire sur les couloirs qui ne sont pas larges
+    #[serde(default)]
+    pub eloignement_murs: f32, // Poids de la pénalité de proximité des murs (0 = désactivée)
+    #[serde(default = "allowed")]
+    pub diagonale_entre_murs: bool, // Autorise les diagonales qui passent entre deux cases infranchissables (comme la carte de base)
+    #[serde(default = "walking_speed")]
+    pub vitesse_marche: f32, // En m/s
+    #[serde(default = "stairs_slowdown")]
+    pub lenteur_escalier: f32, // Durée multipliée dans les escaliers
+    #[serde(default = "elevator_wait")]
+    pub attente_ascenseur: f32, // Attente (s) à chaque ascenseur emprunté
+}
+
+impl Default for RoutingProfile {
+    fn default() -> Self {
+        Self {
+            couloir: walkable(),
+            salle: walkable(),
+            escalier: floor_change(),
+            ascenseur: floor_change(),
+            exterieur: outdoor(),
+            couloir_etroit: neutral(),
+            eloignement_murs: 0.0,
+            diagonale_entre_murs: allowed(),
+            vitesse_marche: walking_speed(),
+            lenteur_escalier: stairs_slowdown(),
+            attente_ascenseur: elevator_wait(),
+        }
+    }
+}
+
+impl RoutingProfile {
+    /// Multiplicateur de coût pour entrer sur une surface (None si infranchissable)
+    pub fn multiplier(&self, surface: SurfaceType) -> Option<f32> {
+        match surface {
+            SurfaceType::Mur => None,
+            SurfaceType::Couloir => self.couloir,
+            SurfaceType::Room(_) => self.salle,
+            SurfaceType::Escalier => self.escalier,
+            SurfaceType::Ascenseur => self.ascenseur,
+            SurfaceType::Exterieur => self.exterieur,
+        }
+    }
+
+    // Les multiplicateurs doivent être strictement positifs pour garder l'heuristique admissible,
+    // comme la vitesse et le ralentissement pour que les durées restent finies
+    fn is_valid(&self) -> bool {
+        [self.couloir, self.salle, self.escalier, self.ascenseur, self.exterieur]
+            .iter()
+            .flatten()
+            .chain([self.couloir_etroit, self.vitesse_marche, self.lenteur_escalier].iter())
+            .all(|m| m.is_finite() && *m > 0.0)
+            && [self.eloignement_murs, self.attente_ascenseur].iter().all(|w| w.is_finite() && *w >= 0.0)
+    }
+}
+
+/// Charge les profils nommés depuis un fichier JSON (`{ "nom": { "escalier": null, ... } }`).
+///
+/// Le profil `default` est toujours disponible ; les profils invalides sont ignorés.
+pub fn load_profiles(filepath: &str) -> BTreeMap<String, RoutingProfile> {
+    info!("📂 Chargement des profils d'itinéraire depuis `{}`", filepath);
+
+    let mut profiles: BTreeMap<String, RoutingProfile> = match fs::read_to_string(filepath) {
+        Ok(content) => serde_json::from_str(&content).unwrap_or_else(|e| {
+            error!("❌ Fichier de profils `{}` invalide : {}", filepath, e);
+            BTreeMap::new()
+        }),
+        Err(e) => {
+            warn!("⚠️ Impossible de lire `{}` ({}), seul le profil par défaut est disponible", filepath, e);
+            BTreeMap::new()
+        }
+    };
+
+    profiles.retain(|name, profile| {
+        let valid = profile.is_valid();
+        if !valid {
+            error!(
+                "⚠️ Profil `{}` ignoré : les multiplicateurs et la vitesse doivent être strictement positifs (pénalité des murs et attente positives ou nulles)",
+                name
+            );
+        }
+        valid
+    });
+    profiles.entry(DEFAULT_PROFILE.to_string()).or_default();
+
+    info!("✅ Profils d'itinéraire disponibles : {:?}", profiles.keys().collect::<Vec<_>>());
+    profiles
+}
+
+/// Coût d'entrée de chaque case pour un profil, sur la grille aplatie (`INFINITY` = infranchissable)
+#[derive(Clone)]
+pub struct CostGrid {
+    pub width: usize,
+    pub height: usize,
+    multipliers: Vec<f32>,
+    min_multiplier: f32,
+    diagonal_squeeze: bool,
+}
+
+impl CostGrid {
+    /// Calcule le coût d'entrée de chaque case de la carte selon le profil
+    pub fn build(map: &Map, profile: &RoutingProfile) -> Self {
+        let wall_distances = wall_distances(map);
+        let mut multipliers = Vec::with_capacity(map.width * map.height);
+        for y in 0..map.height {
+            for x in 0..map.width {
+                let surface = map.get_surface(x, y);
+                let multiplier = match profile.multiplier(surface) {
+                    Some(m) if surface == SurfaceType::Couloir && !map.is_wide_corridor(x, y) => m * profile.couloir_etroit,
+                    Some(m) => m,
+                    None => f32::INFINITY,
+                };
+
+                // Pénalité décroissante avec la distance au mur le plus proche : les trajets passent au milieu des couloirs
+                let closeness = ((CLEARANCE_RANGE - wall_distances[y * map.width + x]) / CLEARANCE_RANGE).max(0.0);
+                multipliers.push(multiplier * (1.0 + profile.eloignement_murs * closeness));
+            }
+        }
+
+        let min_multiplier = multipliers.iter().copied().filter(|m| m.is_finite()).fold(f32::INFINITY, f32::min);
+        let min_multiplier = if min_multiplier.is_finite() { min_multiplier } else { 1.0 };
+
+        Self { width: map.width, height: map.height, multipliers, min_multiplier, diagonal_squeeze: profile.diagonale_entre_murs }
+    }
+
+    /// Multiplicateur de la case (x, y), None si infranchissable ou hors de la carte
+    pub fn multiplier(&self, x: usize, y: usize) -> Option<f32> {
+        if x >= self.width || y >= self.height {
+            return None;
+        }
+        let multiplier = self.multipliers[y * self.width + x];
+        multiplier.is_finite().then_some(multiplier)
+    }
+
+    /// Indique si le pas de `from` vers la case voisine `to` est permis : une diagonale
+    /// ne passe pas entre deux cases infranchissables, sauf si le profil l'autorise
+    pub fn allows_move(&self, from: (usize, usize), to: (usize, usize)) -> bool {
+        if self.diagonal_squeeze || from.0 == to.0 || from.1 == to.1 {
+            return true;
+        }
+        self.multiplier(to.0, from.1).is_some() || self.multiplier(from.0, to.1).is_some()
+    }
+
+    /// Multiplie le coût des cases données par `factor` (≥ 1 : l'estimation reste admissible)
+    pub fn penalize(&mut self, cells: impl IntoIterator<Item = (usize, usize)>, factor: f32) {
+        for (x, y) in cells {
+            if x < self.width && y < self.height {
+                self.multipliers[y * self.width + x] *= factor;
+            }
+        }
+    }
+
+    /// Rend les cases données infranchissables
+    pub fn close(&mut self, cells: impl IntoIterator<Item = (usize, usize)>) {
+        self.penalize(cells, f32::INFINITY);
+    }
+
+    /// Estimation admissible du coût de `a` à `b` : distance octile au multiplicateur minimal
+    pub fn estimate(&self, a: (usize, usize), b: (usize, usize)) -> f32 {
+        heuristic(a, b) * self.min_multiplier
+    }
+}
+
+// Distance (octile, en cases) de chaque case au mur le plus proche, par transformée de distance en deux passes
+fn wall_distances(map: &Map) -> Vec<f32> {
+    let (width, height) = (map.width, map.height);
+    let mut distances: Vec<f32> = (0..width * height)
+        .map(|i| if map.grid[i / width][i % width] == SurfaceType::Mur { 0.0 } else { f32::INFINITY })
+        .collect();
+
+    let relax = |distances: &mut [f32], x: usize, y: usize, dx: isize, dy: isize| {
+        let (nx, ny) = (x as isize + dx, y as isize + dy);
+        if nx < 0 || ny < 0 || nx as usize >= width || ny as usize >= height {
+            return;
+        }
+        let step = if dx != 0 && dy != 0 { 1.4 } else { 1.0 };
+        let candidate = distances[ny as usize * width + nx as usize] + step;
+        let current = &mut distances[y * width + x];
+        *current = current.min(candidate);
+    };
+
+    // Passe avant (voisins déjà traités : gauche et ligne du dessus), puis passe arrière
+    for y in 0..height {
+        for x in 0..width {
+            for (dx, dy) in [(-1, 0), (-1, -1), (0, -1), (1, -1)] {
+                relax(&mut distances, x, y, dx, dy);
+            }
+        }
+    }
+    for y in (0..height).rev() {
+        for x in (0..width).rev() {
+            for (dx, dy) in [(1, 0), (1, 1), (0, 1), (-1, 1)] {
+                relax(&mut distances, x, y, dx, dy);
+            }
+        }
+    }
+
+    distances
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::astar::astar_pathfinding;
+    use crate::navigation::testing::grid;
+
+    // Profils lus depuis un fichier temporaire
+    fn load(name: &str, content: &str) -> BTreeMap<String, RoutingProfile> {
+        let path = std::env::temp_dir().join(format!("navigationservice-profils-{}-{}.json", name, std::process::id()));
+        fs::write(&path, content).unwrap();
+        let profiles = load_profiles(&path.to_string_lossy());
+        let _ = fs::remove_file(&path);
+        profiles
+    }
+
+    #[test]
+    fn default_profile_is_always_available() {
+        assert_eq!(load_profiles("introuvable.json").keys().collect::<Vec<_>>(), vec![DEFAULT_PROFILE]);
+        assert_eq!(load("invalide", "{ pas du json").keys().collect::<Vec<_>>(), vec![DEFAULT_PROFILE]);
+
+        let profiles = load("partiel", r#"{ "lent": { "vitesse_marche": 0.5 } }"#);
+        assert_eq!(profiles.keys().collect::<Vec<_>>(), vec![DEFAULT_PROFILE, "lent"]);
+        assert_eq!(profiles["lent"].escalier, floor_change());
+
+        // Un profil `default` du fichier remplace celui par défaut
+        let profiles = load("remplace", r#"{ "default": { "exterieur": null } }"#);
+        assert_eq!(profiles[DEFAULT_PROFILE].exterieur, None);
+    }
+
+    #[test]
+    fn invalid_profiles_are_rejected() {
+        let profiles = load(
+            "valeurs",
+            r#"{
+                "ok": { "escalier": null, "eloignement_murs": 0.0, "attente_ascenseur": 0.0 },
+                "nul": { "couloir": 0.0 },
+                "negatif": { "salle": -1.0 },
+                "immobile": { "vitesse_marche": 0.0 },
+                "murs": { "eloignement_murs": -0.5 },
+                "attente": { "attente_ascenseur": -1.0 },
+                "escalier": { "lenteur_escalier": 0.0 }
+            }"#,
+        );
+        assert_eq!(profiles.keys().collect::<Vec<_>>(), vec![DEFAULT_PROFILE, "ok"]);
+        assert!(RoutingProfile::default().is_valid());
+        assert!(!RoutingProfile { couloir_etroit: f32::NAN, ..RoutingProfile::default() }.is_valid());
+        assert!(!RoutingProfile { exterieur: Some(f32::INFINITY), ..RoutingProfile::default() }.is_valid());
+    }
+
+    #[test]
+    fn wall_clearance_favours_the_middle_of_corridors() {
+        let map = grid(&[
+            "#########",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#.......#",
+            "#########",
+        ]);
+        let neutral = CostGrid::build(&map, &RoutingProfile::default());
+        assert!((1..8).all(|x| neutral.multiplier(x, 4) == Some(1.0) && neutral.multiplier(x, 1) == Some(1.0)));
+
+        let centered = CostGrid::build(&map, &RoutingProfile { eloignement_murs: 1.0, ..RoutingProfile::default() });
+        let (edge, middle) = (centered.multiplier(4, 1).unwrap(), centered.multiplier(4, 4).unwrap());
+        assert!(edge > middle && middle >= 1.0, "bord {} / milieu {}", edge, middle);
+        assert_eq!(centered.multiplier(0, 0), None);
+    }
+
+    #[test]
+    fn diagonal_squeezes_are_optional() {
+        let map = grid(&[
+            ".#",
+            "#.",
+        ]);
+        let squeeze = CostGrid::build(&map, &RoutingProfile::default());
+        assert!(squeeze.allows_move((0, 0), (1, 1)));
+        assert!(astar_pathfinding(&map, &squeeze, (0, 0), (1, 1)).is_some());
+
+        let careful = CostGrid::build(&map, &RoutingProfile { diagonale_entre_murs: false, ..RoutingProfile::default() });
+        assert!(!careful.allows_move((0, 0), (1, 1)));
+        assert!(careful.allows_move((0, 0), (1, 0)));
+        assert!(astar_pathfinding(&map, &careful, (0, 0), (1, 1)).is_none());
+    }
+
+    #[test]
+    fn wheelchair_never_takes_stairs() {
+        let profiles = load_profiles("data/profiles.json");
+        let wheelchair = &profiles["wheelchair"];
+
+        // Les escaliers sont le raccourci, l'ascenseur le détour
+        let map = grid(&[
+            "1EEE2",
+            ".###.",
+            ".AAA.",
+        ]);
+        let costs = CostGrid::build(&map, wheelchair);
+        let path = astar_pathfinding(&map, &costs, (0, 0), (4, 0)).expect("l'ascenseur reste accessible");
+        assert!(path.iter().all(|&(x, y)| map.get_surface(x, y) != SurfaceType::Escalier), "{:?}", path);
+
+        // Sans autre passage que les escaliers, aucun itinéraire
+        let map = grid(&["1EEE2"]);
+        let costs = CostGrid::build(&map, wheelchair);
+        assert!(astar_pathfinding(&map, &costs, (0, 0), (4, 0)).is_none());
+    }
+}
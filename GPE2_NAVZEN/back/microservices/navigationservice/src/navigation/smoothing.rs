@@ -0,0 +1,217 @@
+// Lissage "any-angle" d'un chemin de grille (tirage de ficelle).
+//
+// A* ne se déplace que dans 8 directions et retourne des chemins en escalier. On
+// part du premier point et on tend la ficelle vers les cases suivantes du chemin
+// tant que la ligne droite reste praticable et ne coûte pas plus que le détour
+// qu'elle remplace (les coûts du profil : surfaces, proximité des murs...). On
+// obtient une polyligne minimale dont les points de passage sont des cases du chemin.
+
+use crate::data::loader::Map;
+use crate::navigation::astar::{compress_moves, get_neighbors, Move, PathSegment};
+use crate::navigation::profile::CostGrid;
+use crate::navigation::travel_time::TravelTime;
+use crate::utils::i18n::Lang;
+
+// Marge acceptée sur le coût d'un raccourci : le coût diagonal de la grille (1.4)
+// sous-estime légèrement la longueur réelle d'une diagonale (√2)
+const SHORTCUT_TOLERANCE: f32 = 1.02;
+
+/// Réduit une suite de cases voisines à ses points de passage (départ et arrivée inclus)
+pub fn smooth_path(costs: &CostGrid, cells: &[(usize, usize)]) -> Vec<(usize, usize)> {
+    let Some(&first) = cells.first() else {
+        return vec![];
+    };
+
+    // Coût cumulé le long du chemin de grille, pour comparer chaque raccourci au détour
+    let mut along = Vec::with_capacity(cells.len());
+    along.push(0.0);
+    for pair in cells.windows(2) {
+        let step = get_neighbors(pair[0], costs)
+            .find(|&(x, y, _)| (x, y) == pair[1])
+            .map_or(f32::INFINITY, |(_, _, cost)| cost);
+        along.push(along[along.len() - 1] + step);
+    }
+
+    let mut waypoints = vec![first];
+    let mut anchor = 0;
+    let mut next = 1;
+    while next < cells.len() {
+        let mut reach = next;
+        while reach + 1 < cells.len()
+            && line_cost(costs, cells[anchor], cells[reach + 1])
+                .is_some_and(|cost| cost <= (along[reach + 1] - along[anchor]) * SHORTCUT_TOLERANCE)
+        {
+            reach += 1;
+        }
+
+        waypoints.push(cells[reach]);
+        anchor = reach;
+        next = reach + 1;
+    }
+
+    waypoints
+}
+
+/// Cases traversées par la ligne droite reliant les centres de deux cases (départ exclu), dans
+/// l'ordre. None si la ligne traverse une case infranchissable ou passe par un coin comme une
+/// diagonale interdite par le profil (voir `CostGrid::allows_move`).
+pub fn line_cells(costs: &CostGrid, from: (usize, usize), to: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+    let (dx, dy) = (to.0 as isize - from.0 as isize, to.1 as isize - from.1 as isize);
+    let (nx, ny) = (dx.abs(), dy.abs());
+    let (sx, sy) = (dx.signum(), dy.signum());
+
+    let (mut x, mut y) = (from.0 as isize, from.1 as isize);
+    let (mut ix, mut iy) = (0, 0);
+    let mut cells = Vec::with_capacity((nx + ny) as usize);
+
+    while ix < nx || iy < ny {
+        let decision = (1 + 2 * ix) * ny - (1 + 2 * iy) * nx;
+        if decision == 0 {
+            // Passage exact par un coin : mêmes règles qu'un pas en diagonale
+            if !costs.allows_move((x as usize, y as usize), ((x + sx) as usize, (y + sy) as usize)) {
+                return None;
+            }
+            x += sx;
+            y += sy;
+            ix += 1;
+            iy += 1;
+        } else if decision < 0 {
+            x += sx;
+            ix += 1;
+        } else {
+            y += sy;
+            iy += 1;
+        }
+
+        costs.multiplier(x as usize, y as usize)?;
+        cells.push((x as usize, y as usize));
+    }
+
+    Some(cells)
+}
+
+/// Coût estimé de la ligne droite entre deux cases : longueur multipliée par le multiplicateur
+/// moyen des cases traversées (None si elle n'est pas praticable, voir `line_cells`)
+pub fn line_cost(costs: &CostGrid, from: (usize, usize), to: (usize, usize)) -> Option<f32> {
+    let cells = line_cells(costs, from, to)?;
+    if cells.is_empty() {
+        return Some(0.0);
+    }
+
+    let total: f32 = cells.iter().filter_map(|&(x, y)| costs.multiplier(x, y)).sum();
+    Some(leg_length(from, to) * total / cells.len() as f32)
+}
+
+fn leg_length(from: (usize, usize), to: (usize, usize)) -> f32 {
+    let (dx, dy) = (to.0 as f32 - from.0 as f32, to.1 as f32 - from.1 as f32);
+    (dx * dx + dy * dy).sqrt()
+}
+
+/// Segments d'un chemin lissé : une ligne droite par paire de points de passage, découpée
+/// là où elle change de surface. Chaque case traversée porte une part égale de la longueur.
+pub fn waypoint_segments(map: &Map, costs: &CostGrid, travel: &TravelTime, waypoints: &[(usize, usize)], lang: Lang) -> Vec<PathSegment> {
+    let mut moves = vec![];
+
+    for (leg, pair) in waypoints.windows(2).enumerate() {
+        let (from, to) = (pair[0], pair[1]);
+        let cells = line_cells(costs, from, to).unwrap_or_else(|| vec![to]);
+        let share = leg_length(from, to) / cells.len() as f32;
+
+        let mut previous = from;
+        for cell in cells {
+            let multiplier = costs.multiplier(cell.0, cell.1).unwrap_or(f32::INFINITY);
+            moves.push(Move {
+                from: previous,
+                to: cell,
+                cost: share * multiplier,
+                seconds: travel.step_seconds(map, previous, cell, share),
+                key: (leg, map.get_surface(cell.0, cell.1)),
+            });
+            previous = cell;
+        }
+    }
+
+    compress_moves(map, waypoints[0], moves, lang)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::astar::{astar_pathfinding, path_cost};
+    use crate::navigation::profile::RoutingProfile;
+    use crate::navigation::testing::{grid, sample_pairs, shipped_map, test_profiles};
+
+    // Vérifie que chaque ligne droite du chemin lissé est praticable et que son coût reste
+    // dans la marge du chemin de grille
+    fn assert_smooth(map: &Map, costs: &CostGrid, cells: &[(usize, usize)]) {
+        let waypoints = smooth_path(costs, cells);
+        assert_eq!(waypoints.first(), cells.first());
+        assert_eq!(waypoints.last(), cells.last());
+
+        let mut total = 0.0;
+        for pair in waypoints.windows(2) {
+            let crossed = line_cells(costs, pair[0], pair[1]).unwrap_or_else(|| panic!("ligne {:?} → {:?} impraticable", pair[0], pair[1]));
+            assert!(crossed.iter().all(|&(x, y)| costs.multiplier(x, y).is_some() && map.contains(x, y)));
+            total += line_cost(costs, pair[0], pair[1]).unwrap();
+        }
+        let grid_cost = path_cost(costs, cells);
+        assert!(total <= grid_cost * SHORTCUT_TOLERANCE + 1e-3, "coût lissé {} pour {} sur la grille", total, grid_cost);
+    }
+
+    #[test]
+    fn smoothed_paths_stay_walkable_and_cheap() {
+        let map = shipped_map();
+        for profile in test_profiles() {
+            let costs = CostGrid::build(&map, &profile);
+            for (start, goal) in sample_pairs(&costs, 15, 34) {
+                if let Some(cells) = astar_pathfinding(&map, &costs, start, goal) {
+                    assert_smooth(&map, &costs, &cells);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn closed_cells_are_never_crossed() {
+        let map = grid(&[
+            ".........",
+            ".........",
+            ".........",
+            ".........",
+        ]);
+        let mut costs = CostGrid::build(&map, &RoutingProfile::default());
+        costs.close([(4, 1), (4, 2), (4, 3)]);
+
+        let cells = astar_pathfinding(&map, &costs, (0, 3), (8, 3)).unwrap();
+        assert_smooth(&map, &costs, &cells);
+        assert_eq!(line_cells(&costs, (0, 3), (8, 3)), None);
+        assert!(smooth_path(&costs, &cells).len() > 2, "la ligne droite traverserait la fermeture");
+    }
+
+    #[test]
+    fn corners_follow_the_diagonal_rule() {
+        let map = grid(&[
+            "..#",
+            ".#.",
+            "#..",
+        ]);
+        // La case (1, 1) sur la diagonale est un mur : la ligne est impraticable
+        let squeeze = CostGrid::build(&map, &RoutingProfile::default());
+        assert_eq!(line_cells(&squeeze, (0, 0), (2, 2)), None);
+
+        // Passage exact par le coin entre deux murs, de (0, 0) à (1, 1)
+        let map = grid(&[
+            ".#.",
+            "#..",
+            "...",
+        ]);
+        let squeeze = CostGrid::build(&map, &RoutingProfile::default());
+        assert_eq!(line_cells(&squeeze, (0, 0), (2, 2)), Some(vec![(1, 1), (2, 2)]));
+        let careful = CostGrid::build(&map, &RoutingProfile { diagonale_entre_murs: false, ..RoutingProfile::default() });
+        assert_eq!(line_cells(&careful, (0, 0), (2, 2)), None);
+        assert_eq!(line_cells(&careful, (1, 1), (2, 2)), Some(vec![(2, 2)]));
+
+        // Une ligne qui ne passe par aucun coin n'est pas concernée
+        assert_eq!(line_cells(&careful, (1, 2), (2, 0)), Some(vec![(1, 1), (2, 1), (2, 0)]));
+    }
+}
@@ -0,0 +1,93 @@
+// Outils communs aux tests des algorithmes de recherche : carte livrée, petites grilles
+// synthétiques et comparaison d'un chemin avec celui de A*.
+
+use crate::data::loader::{Map, SurfaceType};
+use crate::navigation::astar::{astar_pathfinding, path_cost, MOVES};
+use crate::navigation::profile::{CostGrid, RoutingProfile};
+use std::collections::HashMap;
+
+// Écart toléré entre deux coûts de chemin (sommes de f32 dans un ordre différent)
+const EPSILON: f32 = 1e-3;
+
+/// Carte livrée avec le service
+pub fn shipped_map() -> Map {
+    Map::load_from_file("data/SurfaceInfo.txt")
+}
+
+/// Grille synthétique : `#` mur, `.` couloir, `o` extérieur, `E` escalier, `A` ascenseur, `1`-`9` salle
+pub fn grid(rows: &[&str]) -> Map {
+    let grid: Vec<Vec<SurfaceType>> = rows
+        .iter()
+        .map(|row| {
+            row.chars()
+                .map(|c| match c {
+                    '.' => SurfaceType::Couloir,
+                    'o' => SurfaceType::Exterieur,
+                    'E' => SurfaceType::Escalier,
+                    'A' => SurfaceType::Ascenseur,
+                    '1'..='9' => SurfaceType::Room(c as usize - '0' as usize),
+                    _ => SurfaceType::Mur,
+                })
+                .collect()
+        })
+        .collect();
+    let (width, height) = (grid[0].len(), grid.len());
+    Map { grid, width, height, line_map: HashMap::new() }
+}
+
+/// Profils représentatifs : neutre, pondéré (murs, couloirs étroits, extérieur) et sans diagonales entre murs
+pub fn test_profiles() -> Vec<RoutingProfile> {
+    vec![
+        RoutingProfile::default(),
+        RoutingProfile { eloignement_murs: 1.0, couloir_etroit: 2.0, exterieur: Some(6.0), ..RoutingProfile::default() },
+        RoutingProfile { diagonale_entre_murs: false, ..RoutingProfile::default() },
+    ]
+}
+
+/// `count` cases franchissables tirées de façon reproductible (générateur congruentiel)
+pub fn sample_cells(costs: &CostGrid, count: usize, seed: u64) -> Vec<(usize, usize)> {
+    let walkable: Vec<(usize, usize)> = (0..costs.height)
+        .flat_map(|y| (0..costs.width).map(move |x| (x, y)))
+        .filter(|&(x, y)| costs.multiplier(x, y).is_some())
+        .collect();
+
+    let mut state = seed;
+    (0..count)
+        .map(|_| {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            walkable[(state >> 33) as usize % walkable.len()]
+        })
+        .collect()
+}
+
+/// Paires (départ, arrivée) reproductibles parmi les cases franchissables
+pub fn sample_pairs(costs: &CostGrid, count: usize, seed: u64) -> Vec<((usize, usize), (usize, usize))> {
+    let cells = sample_cells(costs, count * 2, seed);
+    cells.chunks(2).map(|pair| (pair[0], pair[1])).collect()
+}
+
+/// Vérifie que `cells` relie `start` à `goal` par des pas permis du profil
+pub fn assert_valid_path(costs: &CostGrid, cells: &[(usize, usize)], start: (usize, usize), goal: (usize, usize)) {
+    assert_eq!(cells.first(), Some(&start), "le chemin ne part pas du départ");
+    assert_eq!(cells.last(), Some(&goal), "le chemin n'arrive pas à destination");
+    for pair in cells.windows(2) {
+        let (dx, dy) = (pair[1].0 as isize - pair[0].0 as isize, pair[1].1 as isize - pair[0].1 as isize);
+        assert!(MOVES.iter().any(|&(mx, my, _)| (mx, my) == (dx, dy)), "cases non voisines : {:?} → {:?}", pair[0], pair[1]);
+        assert!(costs.multiplier(pair[1].0, pair[1].1).is_some(), "case infranchissable {:?}", pair[1]);
+        assert!(costs.allows_move(pair[0], pair[1]), "pas interdit {:?} → {:?}", pair[0], pair[1]);
+    }
+}
+
+/// Vérifie qu'un algorithme trouve un chemin valide de même coût que A* (ou aucun chemin comme lui)
+pub fn assert_matches_astar(map: &Map, costs: &CostGrid, start: (usize, usize), goal: (usize, usize), found: Option<Vec<(usize, usize)>>) {
+    let expected = astar_pathfinding(map, costs, start, goal);
+    match (expected, found) {
+        (None, None) => {}
+        (Some(expected), Some(found)) => {
+            assert_valid_path(costs, &found, start, goal);
+            let (expected, found) = (path_cost(costs, &expected), path_cost(costs, &found));
+            assert!((expected - found).abs() <= EPSILON, "{:?} → {:?} : coût {} au lieu de {}", start, goal, found, expected);
+        }
+        (expected, found) => panic!("{:?} → {:?} : A* {:?}, algorithme {:?}", start, goal, expected.is_some(), found.is_some()),
+    }
+}
@@ -0,0 +1,281 @@
+// Itinéraires dépendant de l'heure : une case à horaires n'est franchissable que si
+// elle est ouverte au moment où l'on y arrive.
+//
+// L'heure d'arrivée sur une case est l'heure de départ plus la durée du chemin pour l'atteindre,
+// somme des durées de ses pas (`TravelTime`, comme les segments de la réponse). Coût et durée
+// ne vont pas toujours ensemble (un couloir rapide mais pénalisé par le profil...) : une case
+// garde donc toutes les étiquettes (coût, durée) qu'aucune autre ne bat sur les deux tableaux,
+// pour ne pas perdre une arrivée plus rapide qui passe encore une porte avant sa fermeture.
+//
+// Limite : la recherche refuse simplement d'entrer dans une case fermée à l'instant d'arrivée.
+// Il n'y a pas d'attente devant une porte, et une étiquette plus chère et plus lente qu'une
+// autre est écartée même si elle seule arriverait après l'ouverture d'une zone.
+
+use crate::data::loader::Map;
+use crate::data::schedules::Calendar;
+use crate::navigation::astar::{get_neighbors, step_length};
+use crate::navigation::buffers::{with_buffers, Node};
+use crate::navigation::profile::CostGrid;
+use crate::navigation::travel_time::TravelTime;
+use log::{info, warn};
+
+// Écart en dessous duquel deux coûts ou deux durées sont considérés égaux
+const EPSILON: f32 = 1e-4;
+
+/// Heure de départ et durées de parcours du profil
+#[derive(Debug, Clone, Copy)]
+pub struct Departure {
+    pub instant: u64, // Heure locale, en secondes (voir `utils::clock`)
+    pub travel: TravelTime,
+}
+
+impl Departure {
+    pub fn new(instant: u64, travel: &TravelTime) -> Self {
+        Self { instant, travel: *travel }
+    }
+
+    /// Heure d'arrivée après `seconds` secondes de trajet
+    pub fn arrival(&self, seconds: f32) -> u64 {
+        self.instant + seconds.round() as u64
+    }
+}
+
+// Entrée dans une case permise si elle est ouverte à l'heure d'arrivée, la durée du trajet
+// étant accumulée pas à pas le long du chemin
+struct OpenOnArrival<'a> {
+    map: &'a Map,
+    calendar: &'a Calendar,
+    departure: &'a Departure,
+}
+
+impl OpenOnArrival<'_> {
+    // Durée (s) d'un pas de `from` vers `to`
+    fn step(&self, from: (usize, usize), to: (usize, usize)) -> f32 {
+        self.departure.travel.step_seconds(self.map, from, to, step_length(from, to))
+    }
+
+    fn admits(&self, (x, y): (usize, usize), seconds: f32) -> bool {
+        self.calendar.is_open(self.map.index(x, y), self.departure.arrival(seconds))
+    }
+}
+
+// Arrivée sur une case par un chemin donné : coût et durée cumulés, étiquette précédente
+#[derive(Clone, Copy)]
+struct Label {
+    cell: usize,
+    cost: f32,
+    seconds: f32,
+    parent: Option<usize>,
+    dominated: bool, // Battue par une étiquette arrivée ensuite : inutile de la développer
+}
+
+// Recherche à étiquettes multiples, développées par coût croissant (plus `estimate`) :
+// la première cible atteinte l'est par le chemin ouvert le moins coûteux
+fn labelled_search(
+    map: &Map,
+    costs: &CostGrid,
+    sources: &[(usize, usize)],
+    is_target: impl Fn(usize) -> bool,
+    estimate: impl Fn((usize, usize)) -> f32,
+    admission: &OpenOnArrival,
+) -> Option<Vec<(usize, usize)>> {
+    if let Some(outside) = sources.iter().find(|&&(x, y)| !map.contains(x, y)) {
+        warn!("❌ Départ {:?} hors de la carte", outside);
+        return None;
+    }
+
+    let mut labels: Vec<Label> = vec![];
+    let mut at_cell: Vec<Vec<usize>> = vec![vec![]; map.width * map.height];
+
+    let found = with_buffers(map.width * map.height, |buffers| {
+        for &(x, y) in sources {
+            let cell = map.index(x, y);
+            at_cell[cell].push(labels.len());
+            buffers.open_set.push(Node { cost: estimate((x, y)), position: labels.len() });
+            labels.push(Label { cell, cost: 0.0, seconds: 0.0, parent: None, dominated: false });
+        }
+
+        while let Some(Node { position: id, .. }) = buffers.pop() {
+            let label = labels[id];
+            if label.dominated {
+                continue;
+            }
+            buffers.count_expansion();
+            if is_target(label.cell) {
+                return Some(id);
+            }
+
+            let current = map.position(label.cell);
+            for (nx, ny, move_cost) in get_neighbors(current, costs) {
+                let neighbor = map.index(nx, ny);
+                let cost = label.cost + move_cost;
+                let seconds = label.seconds + admission.step(current, (nx, ny));
+                if !admission.admits((nx, ny), seconds) {
+                    continue;
+                }
+
+                let beaten = |other: &Label| other.cost <= cost + EPSILON && other.seconds <= seconds + EPSILON;
+                if at_cell[neighbor].iter().any(|&other| beaten(&labels[other])) {
+                    continue;
+                }
+                at_cell[neighbor].retain(|&other| {
+                    let dominated = cost <= labels[other].cost && seconds <= labels[other].seconds;
+                    labels[other].dominated |= dominated;
+                    !dominated
+                });
+
+                at_cell[neighbor].push(labels.len());
+                buffers.open_set.push(Node { cost: cost + estimate((nx, ny)), position: labels.len() });
+                labels.push(Label { cell: neighbor, cost, seconds, parent: Some(id), dominated: false });
+            }
+        }
+
+        None
+    })?;
+
+    // Remonte les étiquettes précédentes jusqu'au départ
+    let mut cells = vec![];
+    let mut current = Some(found);
+    while let Some(id) = current {
+        cells.push(map.position(labels[id].cell));
+        current = labels[id].parent;
+    }
+    cells.reverse();
+    Some(cells)
+}
+
+/// Indique si chaque case du chemin (hors départ) est ouverte à l'heure où on l'atteint
+pub fn respects_schedule(map: &Map, calendar: &Calendar, departure: &Departure, cells: &[(usize, usize)]) -> bool {
+    if calendar.is_empty() {
+        return true;
+    }
+
+    let admission = OpenOnArrival { map, calendar, departure };
+    let mut seconds = 0.0;
+    cells.windows(2).all(|pair| {
+        seconds += admission.step(pair[0], pair[1]);
+        admission.admits(pair[1], seconds)
+    })
+}
+
+/// Chemin le moins coûteux de `sources` vers `targets` ne traversant que des zones ouvertes à
+/// l'heure de passage (voir la limite en tête de module)
+pub fn timed_pathfinding(
+    map: &Map,
+    costs: &CostGrid,
+    calendar: &Calendar,
+    departure: &Departure,
+    sources: &[(usize, usize)],
+    targets: &[(usize, usize)],
+) -> Option<Vec<(usize, usize)>> {
+    info!("🕒 Recherche tenant compte des horaires d'ouverture (départ à {}s)", departure.instant);
+    let admission = OpenOnArrival { map, calendar, departure };
+
+    // Entre deux cases, l'heuristique du profil guide la recherche comme A*
+    let cells = match (sources, targets) {
+        (&[_], &[goal]) if map.contains(goal.0, goal.1) => {
+            let goal_index = map.index(goal.0, goal.1);
+            labelled_search(map, costs, sources, |cell| cell == goal_index, |position| costs.estimate(position, goal), &admission)
+        }
+        _ => {
+            let mut is_target = vec![false; map.width * map.height];
+            for &(x, y) in targets.iter().filter(|&&(x, y)| map.contains(x, y)) {
+                is_target[map.index(x, y)] = true;
+            }
+            labelled_search(map, costs, sources, |cell| is_target[cell], |_| 0.0, &admission)
+        }
+    };
+
+    if cells.is_none() {
+        warn!("❌ Aucun chemin ouvert entre les cases demandées à l'heure de passage");
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::astar::{astar_pathfinding, path_cost, reconstruct_path};
+    use crate::navigation::profile::RoutingProfile;
+    use crate::navigation::testing::grid;
+    use crate::utils::clock::{parse_datetime, Clock, FixedClock};
+    use crate::utils::i18n::Lang;
+
+    // Calendrier lu depuis un fichier temporaire
+    fn calendar(map: &Map, schedules: &str) -> Calendar {
+        let path = std::env::temp_dir().join(format!("navigationservice-horaires-{}.json", std::process::id()));
+        std::fs::write(&path, schedules).unwrap();
+        let calendar = Calendar::load(&path.to_string_lossy(), map);
+        let _ = std::fs::remove_file(&path);
+        calendar
+    }
+
+    #[test]
+    fn arrival_matches_segment_durations() {
+        // L'attente à l'ascenseur compte dans la durée, pas dans le coût
+        let map = grid(&["..A..1"]);
+        let profile = RoutingProfile::default();
+        let costs = CostGrid::build(&map, &profile);
+        let travel = TravelTime::new(&profile, 0.5);
+        let calendar = calendar(&map, r#"[{"area": {"type": "room", "room": 1}, "hours": {"lundi": ["08:00-11:00"]}}]"#);
+
+        let (start, goal) = ((0, 0), (5, 0));
+        let cells: Vec<(usize, usize)> = (0..6).map(|x| (x, 0)).collect();
+        let duration: f32 = reconstruct_path(&map, &costs, &travel, &cells, Lang::Fr).iter().map(|segment| segment.time_s).sum();
+        let closing = parse_datetime("2024-10-14T11:00", 0).unwrap();
+
+        // Arrivée deux secondes avant la fermeture de la salle
+        let clock = FixedClock(closing - duration.round() as u64 - 2);
+        let departure = Departure::new(clock.now(), &travel);
+        assert_eq!(departure.arrival(duration), closing - 2);
+        assert!(respects_schedule(&map, &calendar, &departure, &cells));
+        assert_eq!(timed_pathfinding(&map, &costs, &calendar, &departure, &[start], &[goal]), Some(cells.clone()));
+
+        // Arrivée deux secondes après : la salle est fermée
+        let clock = FixedClock(closing - duration.round() as u64 + 2);
+        let departure = Departure::new(clock.now(), &travel);
+        assert!(!respects_schedule(&map, &calendar, &departure, &cells));
+        assert_eq!(timed_pathfinding(&map, &costs, &calendar, &departure, &[start], &[goal]), None);
+    }
+
+    #[test]
+    fn faster_arrival_beats_cheaper_late_one() {
+        // Le détour par le couloir évite l'extérieur (six fois plus coûteux) mais arrive à
+        // la porte de la salle après sa fermeture ; le raccourci extérieur y arrive à temps.
+        // Les deux chemins se rejoignent en (9, 0), avant l'unique porte (10, 0).
+        let map = grid(&[
+            ".oooooooo..1",
+            ".########.##",
+            ".########.##",
+            ".########.##",
+            "..........##",
+        ]);
+        let profile = RoutingProfile { exterieur: Some(6.0), ..RoutingProfile::default() };
+        let costs = CostGrid::build(&map, &profile);
+        let travel = TravelTime::new(&profile, 1.0);
+        let calendar = calendar(&map, r#"[{"area": {"type": "room", "room": 1}, "hours": {"lundi": ["08:00-11:00"]}}]"#);
+        let duration = |cells: &[(usize, usize)]| -> f32 {
+            reconstruct_path(&map, &costs, &travel, cells, Lang::Fr).iter().map(|segment| segment.time_s).sum()
+        };
+
+        let (start, goal) = ((0, 0), (11, 0));
+        let detour = astar_pathfinding(&map, &costs, start, goal).unwrap();
+        assert!(detour.contains(&(5, 4)), "le chemin le moins coûteux est le détour");
+        let shortcut: Vec<(usize, usize)> = (0..12).map(|x| (x, 0)).collect();
+        assert!(duration(&detour) > duration(&shortcut) + 4.0);
+
+        // Le raccourci arrive deux secondes avant la fermeture, le détour plus de deux secondes après
+        let closing = parse_datetime("2024-10-14T11:00", 0).unwrap();
+        let departure = Departure::new(closing - duration(&shortcut).round() as u64 - 2, &travel);
+        assert!(!respects_schedule(&map, &calendar, &departure, &detour));
+        assert!(respects_schedule(&map, &calendar, &departure, &shortcut));
+
+        assert_eq!(timed_pathfinding(&map, &costs, &calendar, &departure, &[start], &[goal]), Some(shortcut.clone()));
+        assert_eq!(timed_pathfinding(&map, &costs, &calendar, &departure, &[start], &map.room_cells(1)), Some(shortcut));
+
+        // Sans contrainte d'horaire, la recherche retrouve le détour
+        let morning = Departure::new(closing - 3600, &travel);
+        let cells = timed_pathfinding(&map, &costs, &calendar, &morning, &[start], &[goal]).unwrap();
+        assert!((path_cost(&costs, &cells) - path_cost(&costs, &detour)).abs() < 1e-3);
+    }
+}
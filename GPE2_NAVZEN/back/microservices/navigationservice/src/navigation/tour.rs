@@ -0,0 +1,215 @@
+// Ordre de visite de plusieurs arrêts (problème du voyageur de commerce).
+//
+// On part d'une matrice de coûts entre le départ (indice 0) et les arrêts (1..n),
+// obtenue par un Dijkstra à cibles multiples depuis chaque point. Les coûts dépendent de la case
+// d'arrivée : la matrice n'est pas symétrique. Jusqu'à EXACT_LIMIT arrêts, la
+// programmation dynamique de Held-Karp donne l'ordre optimal ; au-delà, on construit
+// une tournée par plus proche voisin puis on l'améliore par inversions (2-opt).
+
+// Held-Karp coûte O(2^n · n²) : 12 arrêts, c'est environ 600 000 opérations
+const EXACT_LIMIT: usize = 12;
+
+/// Ordre de visite des arrêts 1..n (le départ 0 n'est pas inclus) minimisant le coût total,
+/// retour au départ compris si demandé. `cost[i][j]` est le coût de i à j (INFINITY si
+/// inaccessible). None si aucun ordre ne permet de tout visiter.
+pub fn visiting_order(cost: &[Vec<f32>], return_to_start: bool) -> Option<Vec<usize>> {
+    let stops = cost.len().saturating_sub(1);
+    let order = if stops <= EXACT_LIMIT {
+        held_karp(cost, return_to_start)
+    } else {
+        let mut order = nearest_neighbor(cost);
+        two_opt(cost, &mut order, return_to_start);
+        order
+    };
+
+    // Sans tournée complète de coût fini, Held-Karp ne remonte qu'une partie des arrêts
+    (order.len() == stops && tour_cost(cost, &order, return_to_start).is_finite()).then_some(order)
+}
+
+/// Coût d'une tournée partant du départ et visitant les arrêts dans l'ordre donné
+pub fn tour_cost(cost: &[Vec<f32>], order: &[usize], return_to_start: bool) -> f32 {
+    let mut total = 0.0;
+    let mut previous = 0;
+    for &stop in order {
+        total += cost[previous][stop];
+        previous = stop;
+    }
+    if return_to_start {
+        total += cost[previous][0];
+    }
+    total
+}
+
+// best[mask][j] : coût minimal pour partir du départ, visiter les arrêts de `mask` et finir sur j
+fn held_karp(cost: &[Vec<f32>], return_to_start: bool) -> Vec<usize> {
+    let n = cost.len() - 1;
+    if n == 0 {
+        return vec![];
+    }
+
+    let full = (1usize << n) - 1;
+    let mut best = vec![vec![f32::INFINITY; n]; full + 1];
+    let mut previous = vec![vec![usize::MAX; n]; full + 1];
+    for j in 0..n {
+        best[1 << j][j] = cost[0][j + 1];
+    }
+
+    for mask in 1..=full {
+        for j in 0..n {
+            let current = best[mask][j];
+            if mask & (1 << j) == 0 || !current.is_finite() {
+                continue;
+            }
+            for k in 0..n {
+                if mask & (1 << k) != 0 {
+                    continue;
+                }
+                let next = mask | (1 << k);
+                let candidate = current + cost[j + 1][k + 1];
+                if candidate < best[next][k] {
+                    best[next][k] = candidate;
+                    previous[next][k] = j;
+                }
+            }
+        }
+    }
+
+    let closing = |j: usize| best[full][j] + if return_to_start { cost[j + 1][0] } else { 0.0 };
+    let mut last = (0..n).min_by(|&a, &b| closing(a).total_cmp(&closing(b))).unwrap_or(0);
+
+    // Remonte les prédécesseurs depuis le dernier arrêt
+    let mut order = Vec::with_capacity(n);
+    let mut mask = full;
+    while last != usize::MAX {
+        order.push(last + 1);
+        let before = previous[mask][last];
+        mask &= !(1 << last);
+        last = before;
+    }
+    order.reverse();
+    order
+}
+
+// Tournée gloutonne : toujours l'arrêt non visité le moins coûteux depuis la position courante
+fn nearest_neighbor(cost: &[Vec<f32>]) -> Vec<usize> {
+    let mut remaining: Vec<usize> = (1..cost.len()).collect();
+    let mut order = Vec::with_capacity(remaining.len());
+    let mut current = 0;
+
+    while !remaining.is_empty() {
+        let (index, _) = remaining
+            .iter()
+            .enumerate()
+            .min_by(|a, b| cost[current][*a.1].total_cmp(&cost[current][*b.1]))
+            .unwrap_or((0, &0));
+        current = remaining.swap_remove(index);
+        order.push(current);
+    }
+
+    order
+}
+
+// Inverse des portions de la tournée tant que cela la raccourcit. Le graphe n'étant pas
+// symétrique, chaque inversion est évaluée sur le coût complet de la tournée.
+fn two_opt(cost: &[Vec<f32>], order: &mut [usize], return_to_start: bool) {
+    let mut best = tour_cost(cost, order, return_to_start);
+    let mut improved = true;
+
+    while improved {
+        improved = false;
+        for i in 0..order.len() {
+            for j in i + 1..order.len() {
+                order[i..=j].reverse();
+                let candidate = tour_cost(cost, order, return_to_start);
+                if candidate < best - 1e-3 {
+                    best = candidate;
+                    improved = true;
+                } else {
+                    order[i..=j].reverse();
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Matrice asymétrique reproductible (générateur congruentiel), diagonale nulle
+    fn random_costs(points: usize, seed: u64) -> Vec<Vec<f32>> {
+        let mut state = seed;
+        (0..points)
+            .map(|i| {
+                (0..points)
+                    .map(|j| {
+                        state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                        if i == j { 0.0 } else { 1.0 + ((state >> 33) % 100) as f32 }
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    // Coût minimal sur toutes les permutations des arrêts
+    fn brute_force(cost: &[Vec<f32>], return_to_start: bool) -> f32 {
+        fn permute(cost: &[Vec<f32>], order: &mut Vec<usize>, rest: &mut Vec<usize>, return_to_start: bool) -> f32 {
+            if rest.is_empty() {
+                return tour_cost(cost, order, return_to_start);
+            }
+            let mut best = f32::INFINITY;
+            for index in 0..rest.len() {
+                let stop = rest.remove(index);
+                order.push(stop);
+                best = best.min(permute(cost, order, rest, return_to_start));
+                order.pop();
+                rest.insert(index, stop);
+            }
+            best
+        }
+        permute(cost, &mut vec![], &mut (1..cost.len()).collect(), return_to_start)
+    }
+
+    #[test]
+    fn held_karp_matches_brute_force() {
+        for return_to_start in [false, true] {
+            for stops in 1..=7 {
+                for seed in 0..5 {
+                    let cost = random_costs(stops + 1, seed * 31 + stops as u64);
+                    let order = visiting_order(&cost, return_to_start).unwrap();
+
+                    let mut visited = order.clone();
+                    visited.sort_unstable();
+                    assert_eq!(visited, (1..=stops).collect::<Vec<_>>(), "chaque arrêt est visité une fois");
+                    let expected = brute_force(&cost, return_to_start);
+                    let found = tour_cost(&cost, &order, return_to_start);
+                    assert!((found - expected).abs() < 1e-3, "{} arrêts (retour {}) : {} au lieu de {}", stops, return_to_start, found, expected);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn two_opt_never_worsens_nearest_neighbor() {
+        for return_to_start in [false, true] {
+            for stops in EXACT_LIMIT + 1..EXACT_LIMIT + 8 {
+                let cost = random_costs(stops + 1, stops as u64);
+                let greedy = tour_cost(&cost, &nearest_neighbor(&cost), return_to_start);
+                let order = visiting_order(&cost, return_to_start).unwrap();
+
+                assert_eq!(order.len(), stops);
+                assert!(tour_cost(&cost, &order, return_to_start) <= greedy, "{} arrêts : 2-opt moins bon que le plus proche voisin", stops);
+            }
+        }
+    }
+
+    #[test]
+    fn unreachable_stops_have_no_order() {
+        let mut cost = random_costs(4, 1);
+        for row in cost.iter_mut() {
+            row[2] = f32::INFINITY;
+        }
+        assert_eq!(visiting_order(&cost, false), None);
+        assert_eq!(visiting_order(&[vec![0.0]], true), Some(vec![]));
+    }
+}
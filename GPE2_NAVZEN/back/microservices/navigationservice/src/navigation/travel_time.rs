@@ -0,0 +1,58 @@
+// Durées de parcours estimées.
+//
+// Le coût d'un chemin mélange distance et préférences du profil (éloignement des murs,
+// couloirs étroits...) : il ne dit pas combien de temps dure le trajet. La durée se calcule
+// donc à part, à partir de la longueur réelle de chaque pas, de l'échelle de la carte, de la
+// vitesse de marche du profil, d'un ralentissement dans les escaliers et d'une attente fixe
+// à chaque ascenseur.
+
+use crate::data::loader::{Map, SurfaceType};
+use crate::navigation::profile::RoutingProfile;
+
+/// Conversion des longueurs parcourues en secondes pour un profil
+#[derive(Debug, Clone, Copy)]
+pub struct TravelTime {
+    seconds_per_cell: f32, // Durée d'une case droite à la vitesse de marche
+    stairs_factor: f32,    // Durée multipliée dans les escaliers
+    elevator_wait: f32,    // Secondes ajoutées à chaque entrée dans un ascenseur
+}
+
+impl TravelTime {
+    pub fn new(profile: &RoutingProfile, meters_per_cell: f32) -> Self {
+        Self {
+            seconds_per_cell: meters_per_cell / profile.vitesse_marche,
+            stairs_factor: profile.lenteur_escalier,
+            elevator_wait: profile.attente_ascenseur,
+        }
+    }
+
+    /// Durée d'un pas de `length` cases de `from` vers `to`, selon la surface d'arrivée
+    pub fn step_seconds(&self, map: &Map, from: (usize, usize), to: (usize, usize), length: f32) -> f32 {
+        let walking = length * self.seconds_per_cell;
+        match map.get_surface(to.0, to.1) {
+            SurfaceType::Escalier => walking * self.stairs_factor,
+            SurfaceType::Ascenseur if map.get_surface(from.0, from.1) != SurfaceType::Ascenseur => walking + self.elevator_wait,
+            _ => walking,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::navigation::testing::grid;
+
+    #[test]
+    fn step_durations_depend_on_the_surface() {
+        let map = grid(&[".EAA."]);
+        let profile = RoutingProfile { vitesse_marche: 1.0, lenteur_escalier: 3.0, attente_ascenseur: 20.0, ..RoutingProfile::default() };
+        let travel = TravelTime::new(&profile, 0.5);
+
+        assert_eq!(travel.step_seconds(&map, (1, 0), (0, 0), 1.0), 0.5);
+        assert_eq!(travel.step_seconds(&map, (1, 0), (0, 0), 1.4), 0.7);
+        assert_eq!(travel.step_seconds(&map, (0, 0), (1, 0), 1.0), 1.5);
+        // L'attente ne compte qu'à l'entrée dans l'ascenseur
+        assert_eq!(travel.step_seconds(&map, (1, 0), (2, 0), 1.0), 20.5);
+        assert_eq!(travel.step_seconds(&map, (2, 0), (3, 0), 1.0), 0.5);
+    }
+}
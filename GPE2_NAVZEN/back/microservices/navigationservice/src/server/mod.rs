@@ -1,2 +1,3 @@
 pub mod app;
 pub mod routes;
+pub mod state;
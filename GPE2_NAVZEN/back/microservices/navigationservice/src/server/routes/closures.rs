@@ -0,0 +1,121 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use crate::data::closures::{now, Closure, ClosureArea};
+use crate::server::routes::navigation::request_lang;
+use crate::server::state::AppState;
+use crate::utils::i18n::{Lang, Message};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+struct LangQuery {
+    lang: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct ClosureRequest {
+    area: ClosureArea,              // {"type": "rectangle" | "polygon" | "room", ...}
+    reason: Option<String>,         // Motif affiché aux exploitants (nettoyage, travaux...)
+    expires_at: Option<u64>,        // Fin de la fermeture (secondes Unix)
+    duration_minutes: Option<u64>,  // Ou durée à partir de maintenant
+    penalty: Option<f32>,           // Multiplicateur de coût (≥ 1) au lieu d'une fermeture complète
+}
+
+#[derive(Serialize)]
+struct ClosureResponse {
+    success: bool,
+    message: String,
+    closure: Option<Closure>,
+}
+
+impl ClosureResponse {
+    fn failure(lang: Lang, message: Message) -> Self {
+        Self { success: false, message: lang.message(&message), closure: None }
+    }
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/closures", web::get().to(list_closures));
+    cfg.route("/closures", web::post().to(create_closure));
+    cfg.route("/closures/{id}", web::delete().to(delete_closure));
+}
+
+async fn list_closures(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(state.closures())
+}
+
+// Vérifie la zone, la pénalité et l'échéance demandées ; retourne l'expiration en secondes Unix
+fn validate(state: &AppState, request: &ClosureRequest) -> Result<Option<u64>, Message> {
+    validate_area(state, &request.area)?;
+    if request.penalty.is_some_and(|factor| !(factor.is_finite() && factor >= 1.0)) {
+        return Err(Message::InvalidPenalty);
+    }
+    expiry(request.expires_at, request.duration_minutes)
+}
+
+/// Vérifie qu'une zone désigne une salle existante ou au moins une case de la carte
+pub(crate) fn validate_area(state: &AppState, area: &ClosureArea) -> Result<(), Message> {
+    if let ClosureArea::Room { room } = *area {
+        if state.map.room_cells(room).is_empty() {
+            return Err(Message::UnknownRoom(room));
+        }
+    }
+    if area.cells(&state.map).is_empty() {
+        return Err(Message::EmptyClosureArea);
+    }
+    Ok(())
+}
+
+/// Expiration en secondes Unix : date donnée, ou durée à partir de maintenant ; elle doit être à venir
+pub(crate) fn expiry(expires_at: Option<u64>, duration_minutes: Option<u64>) -> Result<Option<u64>, Message> {
+    let from_duration = |minutes: u64| {
+        minutes.checked_mul(60).and_then(|seconds| now().checked_add(seconds)).ok_or(Message::InvalidDuration)
+    };
+    let expires_at = match expires_at {
+        Some(expires_at) => Some(expires_at),
+        None => duration_minutes.map(from_duration).transpose()?,
+    };
+    if expires_at.is_some_and(|expiry| expiry <= now()) {
+        return Err(Message::ExpiryInPast);
+    }
+    Ok(expires_at)
+}
+
+async fn create_closure(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<LangQuery>,
+    body: web::Json<ClosureRequest>,
+) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let content_language = (header::CONTENT_LANGUAGE, lang.code());
+
+    let request = body.into_inner();
+    let expires_at = match validate(&state, &request) {
+        Ok(expires_at) => expires_at,
+        Err(message) => {
+            return HttpResponse::BadRequest().insert_header(content_language).json(ClosureResponse::failure(lang, message));
+        }
+    };
+
+    let closure = state.add_closure(request.area, request.reason, expires_at, request.penalty);
+    HttpResponse::Created().insert_header(content_language).json(ClosureResponse {
+        success: true,
+        message: lang.message(&Message::ClosureCreated),
+        closure: Some(closure),
+    })
+}
+
+async fn delete_closure(req: HttpRequest, state: web::Data<AppState>, path: web::Path<u64>, query: web::Query<LangQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let content_language = (header::CONTENT_LANGUAGE, lang.code());
+    let id = path.into_inner();
+
+    if state.remove_closure(id) {
+        HttpResponse::Ok().insert_header(content_language).json(ClosureResponse {
+            success: true,
+            message: lang.message(&Message::ClosureDeleted),
+            closure: None,
+        })
+    } else {
+        HttpResponse::NotFound().insert_header(content_language).json(ClosureResponse::failure(lang, Message::ClosureNotFound(id)))
+    }
+}
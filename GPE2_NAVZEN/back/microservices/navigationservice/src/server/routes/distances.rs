@@ -0,0 +1,99 @@
+use actix_web::{http::{header, StatusCode}, web, HttpRequest, HttpResponse, Responder};
+use crate::navigation::distances::DistanceMatrix;
+use crate::navigation::profile::DEFAULT_PROFILE;
+use crate::server::routes::navigation::request_lang;
+use crate::server::state::AppState;
+use crate::utils::i18n::{Lang, Message};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+struct DistancesQuery {
+    from: Option<String>, // Salles de départ : "3,8,..." (toutes si absent)
+    to: Option<String>,   // Salles d'arrivée (toutes si absent)
+    profile: Option<String>,
+    lang: Option<String>,
+}
+
+#[derive(Serialize)]
+struct DistancesResponse {
+    success: bool,
+    message: String,
+    version: Option<u64>,                       // Version des coûts (fermetures et dangers) ayant servi au calcul
+    stale: Option<bool>,                        // Coûts modifiés depuis : nouvelles distances en cours de calcul
+    from: Option<Vec<usize>>,                   // Une ligne par salle de départ
+    to: Option<Vec<usize>>,                     // Une colonne par salle d'arrivée
+    distances_m: Option<Vec<Vec<Option<f32>>>>, // Longueur de l'itinéraire le moins coûteux, null si la salle d'arrivée est inaccessible
+    times_s: Option<Vec<Vec<Option<f32>>>>,     // Durée de cet itinéraire pour le profil
+}
+
+impl DistancesResponse {
+    fn failure(lang: Lang, message: Message) -> Self {
+        Self {
+            success: false,
+            message: lang.message(&message),
+            version: None,
+            stale: None,
+            from: None,
+            to: None,
+            distances_m: None,
+            times_s: None,
+        }
+    }
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/distances", web::get().to(room_distances));
+}
+
+// Salles d'une liste "3,8,..." et leur position dans la matrice ; toutes les salles si la liste est absente
+fn parse_rooms(matrix: &DistanceMatrix, value: Option<&str>) -> Result<Vec<(usize, usize)>, Message> {
+    let Some(value) = value.filter(|value| !value.is_empty()) else {
+        return Ok(matrix.rooms.iter().copied().enumerate().map(|(position, room)| (room, position)).collect());
+    };
+
+    value
+        .split(',')
+        .map(|room| {
+            let id: usize = room.trim().parse().map_err(|_| Message::InvalidRoomList(value.to_string()))?;
+            let position = matrix.position(id).ok_or(Message::UnknownRoom(id))?;
+            Ok((id, position))
+        })
+        .collect()
+}
+
+// La matrice est précalculée (en arrière-plan après chaque modification des fermetures) : la
+// réponse est immédiate, sans passer par le pool de calcul
+async fn room_distances(req: HttpRequest, state: web::Data<AppState>, query: web::Query<DistancesQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let (status, body) = distances(&state, &query, lang);
+    HttpResponse::build(status).insert_header((header::CONTENT_LANGUAGE, lang.code())).json(body)
+}
+
+fn distances(state: &AppState, query: &DistancesQuery, lang: Lang) -> (StatusCode, DistancesResponse) {
+    let profile = query.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(routing) = state.routing(profile) else {
+        return (StatusCode::BAD_REQUEST, DistancesResponse::failure(lang, Message::UnknownProfile(profile.to_string())));
+    };
+
+    let matrix = routing.distances();
+    let rooms = parse_rooms(&matrix, query.from.as_deref()).and_then(|from| Ok((from, parse_rooms(&matrix, query.to.as_deref())?)));
+    let (from, to) = match rooms {
+        Ok(rooms) => rooms,
+        Err(message) => return (StatusCode::BAD_REQUEST, DistancesResponse::failure(lang, message)),
+    };
+
+    let table = |value: &dyn Fn(usize, usize) -> Option<f32>| -> Vec<Vec<Option<f32>>> {
+        from.iter().map(|&(_, a)| to.iter().map(|&(_, b)| value(a, b)).collect()).collect()
+    };
+    let response = DistancesResponse {
+        success: true,
+        message: lang.message(&Message::DistancesReady),
+        version: Some(matrix.version),
+        stale: Some(matrix.version != routing.version),
+        distances_m: Some(table(&|a, b| matrix.length(a, b).map(|length| length * state.meters_per_cell))),
+        times_s: Some(table(&|a, b| matrix.seconds(a, b))),
+        from: Some(from.iter().map(|&(room, _)| room).collect()),
+        to: Some(to.iter().map(|&(room, _)| room).collect()),
+    };
+    (StatusCode::OK, response)
+}
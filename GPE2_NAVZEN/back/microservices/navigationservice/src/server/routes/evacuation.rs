@@ -0,0 +1,164 @@
+use actix_web::{http::header, web, HttpRequest, HttpResponse, Responder};
+use crate::data::closures::{Closure, ClosureArea};
+use crate::navigation::evacuation::moves;
+use crate::navigation::profile::DEFAULT_PROFILE;
+use crate::server::routes::closures::{expiry, validate_area};
+use crate::server::routes::navigation::request_lang;
+use crate::server::state::AppState;
+use crate::utils::i18n::{Lang, Message};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+struct EvacuationQuery {
+    profile: Option<String>,
+    lang: Option<String>,
+}
+
+#[derive(Serialize)]
+struct EvacuationResponse {
+    success: bool,
+    message: String,
+    version: Option<u64>,                // Change à chaque déclaration ou levée d'un danger (aussi dans l'ETag)
+    stale: Option<bool>,                 // Dangers ou fermetures modifiés depuis : nouveau champ en cours de calcul
+    width: Option<usize>,
+    height: Option<usize>,
+    moves: Option<Vec<(isize, isize)>>,  // Déplacement (dx, dy) désigné par chaque chiffre du champ
+    directions: Option<Vec<String>>,     // Une ligne par y : chiffre du déplacement, `S` sortie, `.` aucun chemin
+    exits: Option<Vec<String>>,          // Sorties désignées (les cases extérieures sont aussi des sorties)
+    hazards: Option<Vec<Closure>>,
+    max_time_s: Option<f32>,             // Temps d'évacuation le plus long en suivant le champ, à la vitesse du profil
+    stranded_cells: Option<usize>,       // Cases praticables coupées de toute sortie
+}
+
+impl EvacuationResponse {
+    fn failure(lang: Lang, message: Message) -> Self {
+        Self {
+            success: false,
+            message: lang.message(&message),
+            version: None,
+            stale: None,
+            width: None,
+            height: None,
+            moves: None,
+            directions: None,
+            exits: None,
+            hazards: None,
+            max_time_s: None,
+            stranded_cells: None,
+        }
+    }
+}
+
+#[derive(Deserialize)]
+struct LangQuery {
+    lang: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct HazardRequest {
+    area: ClosureArea,             // {"type": "rectangle" | "polygon" | "room", ...}
+    reason: Option<String>,        // Nature du danger (incendie, fumées, inondation...)
+    expires_at: Option<u64>,       // Levée automatique (secondes Unix)
+    duration_minutes: Option<u64>, // Ou durée à partir de maintenant
+}
+
+#[derive(Serialize)]
+struct HazardResponse {
+    success: bool,
+    message: String,
+    hazard: Option<Closure>,
+}
+
+impl HazardResponse {
+    fn failure(lang: Lang, message: Message) -> Self {
+        Self { success: false, message: lang.message(&message), hazard: None }
+    }
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/evacuation", web::get().to(evacuation_field));
+    cfg.route("/evacuation/hazards", web::get().to(list_hazards));
+    cfg.route("/evacuation/hazards", web::post().to(declare_hazard));
+    cfg.route("/evacuation/hazards/{id}", web::delete().to(remove_hazard));
+}
+
+// Champ complet, à télécharger d'avance : les clients guident ensuite sans le serveur.
+// L'ETag permet de ne retélécharger le champ que s'il a changé.
+async fn evacuation_field(req: HttpRequest, state: web::Data<AppState>, query: web::Query<EvacuationQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let content_language = (header::CONTENT_LANGUAGE, lang.code());
+
+    let profile = query.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(routing) = state.routing(profile) else {
+        return HttpResponse::BadRequest()
+            .insert_header(content_language)
+            .json(EvacuationResponse::failure(lang, Message::UnknownProfile(profile.to_string())));
+    };
+
+    let etag = format!("\"{}-{}-{}\"", profile, routing.version, lang.code());
+    let unchanged = req.headers().get(header::IF_NONE_MATCH).and_then(|value| value.to_str().ok()) == Some(etag.as_str());
+    if unchanged {
+        return HttpResponse::NotModified().insert_header((header::ETAG, etag)).finish();
+    }
+
+    let field = &routing.evacuation;
+    HttpResponse::Ok().insert_header(content_language).insert_header((header::ETAG, etag)).json(EvacuationResponse {
+        success: true,
+        message: lang.message(&Message::EvacuationReady),
+        version: Some(routing.version),
+        stale: Some(state.rebuild_pending()),
+        width: Some(state.map.width),
+        height: Some(state.map.height),
+        moves: Some(moves()),
+        directions: Some(field.rows(&state.map)),
+        exits: Some(state.exits.iter().map(|exit| exit.name.clone()).collect()),
+        hazards: Some(routing.hazards.clone()),
+        max_time_s: Some(field.max_seconds()),
+        stranded_cells: Some(field.stranded_cells(&routing.costs, &state.map)),
+    })
+}
+
+async fn list_hazards(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(state.hazards())
+}
+
+async fn declare_hazard(
+    req: HttpRequest,
+    state: web::Data<AppState>,
+    query: web::Query<LangQuery>,
+    body: web::Json<HazardRequest>,
+) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let content_language = (header::CONTENT_LANGUAGE, lang.code());
+
+    let request = body.into_inner();
+    let expires_at = match validate_area(&state, &request.area).and_then(|_| expiry(request.expires_at, request.duration_minutes)) {
+        Ok(expires_at) => expires_at,
+        Err(message) => {
+            return HttpResponse::BadRequest().insert_header(content_language).json(HazardResponse::failure(lang, message));
+        }
+    };
+
+    let hazard = state.add_hazard(request.area, request.reason, expires_at);
+    HttpResponse::Created().insert_header(content_language).json(HazardResponse {
+        success: true,
+        message: lang.message(&Message::HazardDeclared),
+        hazard: Some(hazard),
+    })
+}
+
+async fn remove_hazard(req: HttpRequest, state: web::Data<AppState>, path: web::Path<u64>, query: web::Query<LangQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let content_language = (header::CONTENT_LANGUAGE, lang.code());
+    let id = path.into_inner();
+
+    if state.remove_hazard(id) {
+        HttpResponse::Ok().insert_header(content_language).json(HazardResponse {
+            success: true,
+            message: lang.message(&Message::HazardRemoved),
+            hazard: None,
+        })
+    } else {
+        HttpResponse::NotFound().insert_header(content_language).json(HazardResponse::failure(lang, Message::HazardNotFound(id)))
+    }
+}
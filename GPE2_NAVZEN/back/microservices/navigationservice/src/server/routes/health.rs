@@ -0,0 +1,28 @@
+use actix_web::{web, HttpResponse, Responder};
+use crate::server::state::AppState;
+use crate::services::route_cache::CacheMetrics;
+use serde::Serialize;
+
+#[derive(Serialize)]
+struct HealthResponse {
+    status: &'static str,
+    compute_threads: usize,
+    pending_searches: usize, // Recherches en cours ou en attente sur le pool de calcul
+    max_pending_searches: usize,
+    route_cache: CacheMetrics,
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/health", web::get().to(health));
+}
+
+// Ne passe jamais par le pool de calcul : répond même quand les recherches sont saturées
+async fn health(state: web::Data<AppState>) -> impl Responder {
+    HttpResponse::Ok().json(HealthResponse {
+        status: "ok",
+        compute_threads: state.compute.threads(),
+        pending_searches: state.compute.pending(),
+        max_pending_searches: state.compute.capacity(),
+        route_cache: state.route_cache.metrics(),
+    })
+}
@@ -0,0 +1,184 @@
+use actix_web::{http::StatusCode, web, HttpRequest, Responder};
+use crate::data::loader::SurfaceType;
+use crate::navigation::budget::SearchBudget;
+use crate::navigation::isochrone::{all_rooms, mask_spans, outlines, reachable_rooms, Limit, Span};
+use crate::navigation::nearest::Facility;
+use crate::navigation::profile::DEFAULT_PROFILE;
+use crate::server::routes::navigation::{request_lang, run_search, search_failure};
+use crate::server::state::AppState;
+use crate::services::pathfinding::{find_reachable, SearchStats};
+use crate::utils::i18n::{Lang, Message};
+use serde::{Serialize, Deserialize};
+
+#[derive(Deserialize)]
+struct IsochroneQuery {
+    start_x: Option<usize>,
+    start_y: Option<usize>,
+    from_room: Option<usize>,   // Depuis n'importe quelle case de cette salle
+    from_type: Option<String>,  // Depuis tous les lieux d'un type ("sortie", "ascenseur"... comme /navigate/nearest)
+    max_distance: Option<f32>,  // Budget en mètres, le long des itinéraires du profil
+    max_time: Option<f32>,      // Ou en secondes, durée de ces itinéraires pour le profil
+    format: Option<String>,     // "salles" (par défaut), "masque" ou "polygone" (ou leur nom anglais)
+    profile: Option<String>,
+    lang: Option<String>,
+}
+
+/// Représentation de la zone accessible demandée
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Format {
+    Mask,
+    Polygon,
+    Rooms,
+}
+
+impl Format {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_ascii_lowercase().as_str() {
+            "masque" | "mask" => Some(Format::Mask),
+            "polygone" | "polygon" => Some(Format::Polygon),
+            "salles" | "rooms" => Some(Format::Rooms),
+            _ => None,
+        }
+    }
+}
+
+/// Salle accessible dans le budget, avec la longueur et la durée de l'itinéraire vers sa case la moins coûteuse à atteindre
+#[derive(Serialize)]
+struct ReachableRoom {
+    room: usize,
+    name: String,
+    distance_m: f32,
+    time_s: f32,
+}
+
+#[derive(Serialize)]
+struct IsochroneResponse {
+    success: bool,
+    message: String,
+    reachable_cells: Option<usize>,
+    area_m2: Option<f32>,
+    mask: Option<Vec<Span>>,                      // Format "masque" : bandes de cases par ligne
+    polygons: Option<Vec<Vec<(usize, usize)>>>,   // Format "polygone" : contours en coins de cases
+    rooms: Option<Vec<ReachableRoom>>,            // Format "salles" : salles atteintes, par coût croissant de leur itinéraire
+    unreachable_rooms: Option<Vec<usize>>,        // Format "salles" : salles hors budget ou inaccessibles
+    search: Option<SearchStats>,
+}
+
+impl IsochroneResponse {
+    fn failure(lang: Lang, message: Message) -> Self {
+        Self {
+            success: false,
+            message: lang.message(&message),
+            reachable_cells: None,
+            area_m2: None,
+            mask: None,
+            polygons: None,
+            rooms: None,
+            unreachable_rooms: None,
+            search: None,
+        }
+    }
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/navigate/isochrone", web::get().to(find_isochrone));
+}
+
+// Cases de départ : salle, type de lieu ou case (x, y), dans cet ordre de priorité
+fn origin_cells(state: &AppState, query: &IsochroneQuery) -> Result<Vec<(usize, usize)>, Message> {
+    if let Some(room) = query.from_room {
+        let cells = state.map.room_cells(room);
+        return if cells.is_empty() { Err(Message::UnknownRoom(room)) } else { Ok(cells) };
+    }
+
+    if let Some(name) = query.from_type.as_deref() {
+        let facility = Facility::parse(name).ok_or_else(|| Message::UnknownFacility(name.to_string()))?;
+        let cells: Vec<(usize, usize)> = (0..state.map.height)
+            .flat_map(|y| (0..state.map.width).map(move |x| (x, y)))
+            .filter(|&cell| facility.matches(&state.map, cell))
+            .collect();
+        return if cells.is_empty() { Err(Message::NoFacilityFound) } else { Ok(cells) };
+    }
+
+    match query.start_x.zip(query.start_y) {
+        Some((x, y)) if !state.map.contains(x, y) => Err(Message::OutsideMap((x, y))),
+        Some(cell) => Ok(vec![cell]),
+        None => Err(Message::MissingOrigin),
+    }
+}
+
+// Limite de la zone : budget en mètres ou en secondes, l'un ou l'autre
+fn limit(state: &AppState, query: &IsochroneQuery) -> Result<Limit, Message> {
+    let (limit, value) = match (query.max_distance, query.max_time) {
+        (Some(meters), None) => (Limit::Length(meters / state.meters_per_cell), meters),
+        (None, Some(seconds)) => (Limit::Seconds(seconds), seconds),
+        _ => return Err(Message::InvalidBudget),
+    };
+    if value.is_finite() && value > 0.0 { Ok(limit) } else { Err(Message::InvalidBudget) }
+}
+
+async fn find_isochrone(req: HttpRequest, state: web::Data<AppState>, query: web::Query<IsochroneQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let budget = SearchBudget::new(state.search_limits);
+    let _cancel = budget.cancel_on_drop();
+
+    let query = query.into_inner();
+    run_search(&state, lang, move |state| compute_isochrone(state, &query, lang, &budget), IsochroneResponse::failure).await
+}
+
+// Calcul de `/navigate/isochrone`, exécuté sur le pool de calcul
+fn compute_isochrone(state: &AppState, query: &IsochroneQuery, lang: Lang, budget: &SearchBudget) -> (StatusCode, IsochroneResponse) {
+    let profile = query.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(routing) = state.routing(profile) else {
+        return (StatusCode::BAD_REQUEST, IsochroneResponse::failure(lang, Message::UnknownProfile(profile.to_string())));
+    };
+
+    let format = match query.format.as_deref() {
+        None => Ok(Format::Rooms),
+        Some(name) => Format::parse(name).ok_or_else(|| Message::UnknownIsochroneFormat(name.to_string())),
+    };
+    let prepared = format.and_then(|format| Ok((format, origin_cells(state, query)?, limit(state, query)?)));
+    let (format, sources, limit) = match prepared {
+        Ok(prepared) => prepared,
+        Err(Message::NoFacilityFound) => return (StatusCode::NOT_FOUND, IsochroneResponse::failure(lang, Message::NoFacilityFound)),
+        Err(message) => return (StatusCode::BAD_REQUEST, IsochroneResponse::failure(lang, message)),
+    };
+
+    let (reached, stats) = find_reachable(&state.map, &routing.costs, &routing.travel, &sources, limit, budget);
+    if stats.aborted.is_some() {
+        let (status, message) = search_failure(&stats, Message::NoPathFound);
+        let mut response = IsochroneResponse::failure(lang, message);
+        response.search = Some(stats);
+        return (status, response);
+    }
+
+    let mut response = IsochroneResponse::failure(lang, Message::IsochroneFound);
+    response.success = true;
+    response.reachable_cells = Some(reached.len());
+    response.area_m2 = Some(reached.len() as f32 * state.meters_per_cell * state.meters_per_cell);
+    response.search = Some(stats);
+
+    match format {
+        Format::Mask => response.mask = Some(mask_spans(&state.map, &reached)),
+        Format::Polygon => response.polygons = Some(outlines(&state.map, &reached)),
+        Format::Rooms => {
+            let rooms = reachable_rooms(&state.map, &reached);
+            response.unreachable_rooms = Some(
+                all_rooms(&state.map).into_iter().filter(|room| !rooms.iter().any(|(reached, _)| reached == room)).collect(),
+            );
+            response.rooms = Some(
+                rooms
+                    .into_iter()
+                    .map(|(room, cell)| ReachableRoom {
+                        room,
+                        name: lang.surface_name(SurfaceType::Room(room)),
+                        distance_m: cell.length * state.meters_per_cell,
+                        time_s: cell.seconds,
+                    })
+                    .collect(),
+            );
+        }
+    }
+
+    (StatusCode::OK, response)
+}
@@ -1,8 +1,21 @@
+pub mod closures;
+pub mod distances;
+pub mod evacuation;
+pub mod health;
+pub mod isochrone;
 pub mod navigation;
 pub mod localization;
 
 use actix_web::web;
 
 pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.service(web::scope("").configure(navigation::configure));
+    cfg.service(
+        web::scope("")
+            .configure(navigation::configure)
+            .configure(isochrone::configure)
+            .configure(closures::configure)
+            .configure(distances::configure)
+            .configure(evacuation::configure)
+            .configure(health::configure),
+    );
 }
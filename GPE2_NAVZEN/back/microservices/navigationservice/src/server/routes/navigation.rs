@@ -1,124 +1,506 @@
-use actix_web::{web, HttpResponse, Responder};
-use crate::data::loader::{Map, SurfaceType};
-use crate::navigation::astar::{astar_pathfinding, PathSegment}; 
-use log::{info, warn};
+use actix_web::{http::header, http::StatusCode, web, HttpRequest, HttpResponse, Responder};
+use crate::navigation::astar::{path_cost, PathSegment};
+use crate::navigation::budget::{AbortReason, SearchBudget};
+use crate::navigation::instructions::{generate_instructions, Instruction};
+use crate::navigation::nearest::Facility;
+use crate::server::state::AppState;
+use crate::navigation::profile::DEFAULT_PROFILE;
+use crate::navigation::timed::{respects_schedule, Departure};
+use crate::services::compute::ComputeError;
+use crate::services::route_cache::{Place, RouteKey};
+use crate::services::pathfinding::{
+    find_alternatives, find_area_path, find_nearest, find_optimal_path, find_timed_path, path_segments, plan_tour, select_pathfinder,
+    SearchStats,
+};
+use crate::utils::clock::{format_datetime, parse_datetime};
+use crate::utils::i18n::{Lang, Message};
 use serde::{Serialize, Deserialize};
 
 #[derive(Deserialize)]
 struct PathQuery {
-    start_x: usize,
-    start_y: usize,
-    end_x: usize,
-    end_y: usize,
+    start_x: Option<usize>,
+    start_y: Option<usize>,
+    end_x: Option<usize>,
+    end_y: Option<usize>,
+    from_room: Option<usize>,  // Part de la porte la mieux placée de cette salle (à la place de start_x/start_y)
+    to_room: Option<usize>,    // Arrive par la porte la mieux placée de cette salle (à la place de end_x/end_y)
+    algorithm: Option<String>, // "astar" (par défaut), "dijkstra", "jps", "bidirectional", "hpa" ou "alt" ; ignoré si une salle est donnée
+    profile: Option<String>,   // Profil d'itinéraire ("default" si absent)
+    smooth: Option<bool>,      // Lissage any-angle : segments droits de point de passage en point de passage
+    cells: Option<bool>,       // Renvoie aussi la liste complète des cases du chemin
+    alternatives: Option<usize>, // Nombre d'itinéraires alternatifs demandés (MAX_ALTERNATIVES au plus)
+    departure_time: Option<String>, // Heure locale de départ, "AAAA-MM-JJTHH:MM" ou "HH:MM" (maintenant si absent)
+    lang: Option<String>,      // "fr" ou "en", prioritaire sur l'en-tête Accept-Language
 }
 
+// Chaque alternative coûte plusieurs recherches A* sur une grille pénalisée
+const MAX_ALTERNATIVES: usize = 3;
+
 #[derive(Serialize)]
 struct PathResponse {
     success: bool,
     message: String,
+    total_cost: Option<f32>,
+    total_time_s: Option<f32>,    // Durée estimée : vitesse du profil, escaliers et attente des ascenseurs
+    departure_time: Option<String>,
+    arrival_time: Option<String>, // Départ + durée estimée
     segments: Option<Vec<PathSegment >>,
+    instructions: Option<Vec<Instruction>>,
+    cells: Option<Vec<(usize, usize)>>,
+    alternatives: Option<Vec<AlternativeRoute>>,
+    search: Option<SearchStats>,
 }
 
-pub fn configure(cfg: &mut web::ServiceConfig) {
-    cfg.route("/navigate", web::get().to(find_path));
+impl PathResponse {
+    fn failure(lang: Lang, message: Message, search: Option<SearchStats>) -> Self {
+        Self {
+            success: false,
+            message: lang.message(&message),
+            total_cost: None,
+            total_time_s: None,
+            departure_time: None,
+            arrival_time: None,
+            segments: None,
+            instructions: None,
+            cells: None,
+            alternatives: None,
+            search,
+        }
+    }
+}
+
+/// Itinéraire alternatif, décrit comme l'itinéraire principal
+#[derive(Serialize)]
+struct AlternativeRoute {
+    total_cost: f32,
+    total_time_s: f32,
+    segments: Vec<PathSegment>,
+    instructions: Vec<Instruction>,
+    cells: Option<Vec<(usize, usize)>>,
+}
+
+// Au-delà, l'ordre de visite n'est plus calculé exactement et les n + 1 recherches (une par point,
+// vers tous les autres) deviennent trop longues pour une requête
+const MAX_STOPS: usize = 15;
+
+#[derive(Deserialize)]
+struct TourQuery {
+    start_x: usize,
+    start_y: usize,
+    stops: Option<String>,         // Cases à visiter : "x,y;x,y;..."
+    rooms: Option<String>,         // Salles à visiter : "3,8,..." (case la plus proche du centre de chaque salle)
+    return_to_start: Option<bool>, // Termine la tournée sur la case de départ
+    profile: Option<String>,
+    smooth: Option<bool>,
+    lang: Option<String>,
+}
+
+#[derive(Serialize)]
+struct TourResponse {
+    success: bool,
+    message: String,
+    order: Option<Vec<(usize, usize)>>, // Arrêts dans l'ordre de visite
+    total_cost: Option<f32>,
+    total_time_s: Option<f32>,
+    segments: Option<Vec<PathSegment>>,
+    instructions: Option<Vec<Instruction>>,
+    search: Option<SearchStats>,
 }
 
-async fn find_path(query: web::Query<PathQuery>) -> impl Responder {
-    let map = Map::load_from_file("data/SurfaceInfo.txt");
+impl TourResponse {
+    fn failure(lang: Lang, message: Message, search: Option<SearchStats>) -> Self {
+        Self {
+            success: false,
+            message: lang.message(&message),
+            order: None,
+            total_cost: None,
+            total_time_s: None,
+            segments: None,
+            instructions: None,
+            search,
+        }
+    }
+}
 
-    match astar_pathfinding(&map, (query.start_x, query.start_y), (query.end_x, query.end_y)) {
-        Some(segments) => HttpResponse::Ok().json(PathResponse {
-            success: true,
-            message: "Chemin trouvé avec succès".to_string(),
-            segments: Some(segments),
-        }),
-        None => HttpResponse::NotFound().json(PathResponse {
+#[derive(Deserialize)]
+struct NearestQuery {
+    start_x: usize,
+    start_y: usize,
+    #[serde(rename = "type")]
+    facility: String, // "sortie", "ascenseur", "escalier", "salle", "couloir" ou "exterieur" (ou leur nom anglais)
+    profile: Option<String>,
+    smooth: Option<bool>,
+    cells: Option<bool>,
+    lang: Option<String>,
+}
+
+#[derive(Serialize)]
+struct NearestResponse {
+    success: bool,
+    message: String,
+    target: Option<(usize, usize)>,
+    target_surface: Option<String>,
+    segments: Option<Vec<PathSegment>>,
+    instructions: Option<Vec<Instruction>>,
+    cells: Option<Vec<(usize, usize)>>,
+    search: Option<SearchStats>,
+}
+
+impl NearestResponse {
+    fn failure(lang: Lang, message: Message, search: Option<SearchStats>) -> Self {
+        Self {
             success: false,
-            message: "Aucun chemin trouvé.".to_string(),
+            message: lang.message(&message),
+            target: None,
+            target_surface: None,
             segments: None,
-        }),
+            instructions: None,
+            cells: None,
+            search,
+        }
+    }
+}
+
+pub fn configure(cfg: &mut web::ServiceConfig) {
+    cfg.route("/navigate", web::get().to(find_path));
+    cfg.route("/navigate/tour", web::get().to(find_tour));
+    cfg.route("/navigate/nearest", web::get().to(find_nearest_facility));
+}
+
+/// Départ ou arrivée d'un trajet : une case précise ou une salle entière
+enum Endpoint {
+    Cell((usize, usize)),
+    Room(usize, Vec<(usize, usize)>),
+}
+
+impl Endpoint {
+    // Salle si elle est demandée, sinon la case (x, y) ; None si aucun des deux n'est fourni
+    fn resolve(state: &AppState, room: Option<usize>, x: Option<usize>, y: Option<usize>) -> Result<Self, Message> {
+        match (room, x.zip(y)) {
+            (Some(id), _) => {
+                let cells = state.map.room_cells(id);
+                if cells.is_empty() {
+                    return Err(Message::UnknownRoom(id));
+                }
+                Ok(Endpoint::Room(id, cells))
+            }
+            (None, Some(cell)) => Ok(Endpoint::Cell(cell)),
+            (None, None) => Err(Message::MissingEndpoint),
+        }
+    }
+
+    fn cells(&self) -> &[(usize, usize)] {
+        match self {
+            Endpoint::Cell(cell) => std::slice::from_ref(cell),
+            Endpoint::Room(_, cells) => cells,
+        }
+    }
+
+    // Départ ou arrivée tel que demandé, pour la clé du cache
+    fn place(&self) -> Place {
+        match self {
+            Endpoint::Cell(cell) => Place::Cell(*cell),
+            Endpoint::Room(id, _) => Place::Room(*id),
+        }
+    }
+}
+
+/// Statut et message d'une recherche sans résultat exploitable : interrompue (503 si une limite
+/// est atteinte, 504 si le délai est dépassé) ou réellement sans issue (404 et `not_found`)
+pub fn search_failure(stats: &SearchStats, not_found: Message) -> (StatusCode, Message) {
+    match stats.aborted {
+        Some(AbortReason::Timeout) => (StatusCode::GATEWAY_TIMEOUT, Message::SearchAborted(AbortReason::Timeout)),
+        Some(reason) => (StatusCode::SERVICE_UNAVAILABLE, Message::SearchAborted(reason)),
+        None => (StatusCode::NOT_FOUND, not_found),
+    }
+}
+
+/// Exécute `search` sur le pool de calcul et envoie sa réponse. Si le pool est saturé (503,
+/// avec `Retry-After`) ou si la recherche panique (500), `failure` construit le corps d'erreur
+pub async fn run_search<T: Serialize + Send + 'static>(
+    state: &web::Data<AppState>,
+    lang: Lang,
+    search: impl FnOnce(&AppState) -> (StatusCode, T) + Send + 'static,
+    failure: impl FnOnce(Lang, Message) -> T,
+) -> HttpResponse {
+    let job_state = state.clone();
+    let result = state.compute.run(move || search(&job_state)).await;
+
+    let busy = matches!(result, Err(ComputeError::Busy));
+    let (status, body) = match result {
+        Ok(response) => response,
+        Err(ComputeError::Busy) => (StatusCode::SERVICE_UNAVAILABLE, failure(lang, Message::ServerBusy)),
+        Err(ComputeError::Panicked) => (StatusCode::INTERNAL_SERVER_ERROR, failure(lang, Message::SearchCrashed)),
+    };
+
+    let mut response = HttpResponse::build(status);
+    response.insert_header((header::CONTENT_LANGUAGE, lang.code()));
+    if busy {
+        response.insert_header((header::RETRY_AFTER, "1"));
     }
+    response.json(body)
 }
 
+// Durée estimée d'un itinéraire : somme des durées de ses segments
+fn total_time(segments: &[PathSegment]) -> f32 {
+    segments.iter().map(|segment| segment.time_s).sum()
+}
 
+/// Langue de la réponse : paramètre `lang`, puis en-tête `Accept-Language`
+pub fn request_lang(req: &HttpRequest, param: Option<&str>) -> Lang {
+    let accept_language = req.headers().get(header::ACCEPT_LANGUAGE).and_then(|value| value.to_str().ok());
+    Lang::negotiate(param, accept_language)
+}
 
+async fn find_path(req: HttpRequest, state: web::Data<AppState>, query: web::Query<PathQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let budget = SearchBudget::new(state.search_limits);
+    let _cancel = budget.cancel_on_drop(); // Requête abandonnée (client déconnecté) : la recherche s'arrête
 
+    let query = query.into_inner();
+    run_search(&state, lang, move |state| route_path(state, &query, lang, &budget), |lang, message| {
+        PathResponse::failure(lang, message, None)
+    })
+    .await
+}
 
+// Recherche de `/navigate`, exécutée sur le pool de calcul
+fn route_path(state: &AppState, query: &PathQuery, lang: Lang, budget: &SearchBudget) -> (StatusCode, PathResponse) {
+    let profile = query.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(routing) = state.routing(profile) else {
+        return (StatusCode::BAD_REQUEST, PathResponse::failure(lang, Message::UnknownProfile(profile.to_string()), None));
+    };
 
+    let prepared = Endpoint::resolve(state, query.from_room, query.start_x, query.start_y).and_then(|start| {
+        let end = Endpoint::resolve(state, query.to_room, query.end_x, query.end_y)?;
+        let departure = match query.departure_time.as_deref() {
+            Some(value) => parse_datetime(value, state.clock.now()).ok_or_else(|| Message::InvalidDepartureTime(value.to_string()))?,
+            None => state.clock.now(),
+        };
+        // Une salle au départ ou à l'arrivée : une seule recherche à sources et cibles multiples
+        // (Dijkstra) choisit les portes, l'algorithme demandé ne sert qu'entre deux cases
+        let cell_to_cell = match (&start, &end) {
+            (Endpoint::Cell(start), Endpoint::Cell(end)) => {
+                let pathfinder = select_pathfinder(query.algorithm.as_deref(), &routing.regions, routing.landmarks.as_deref())?;
+                Some((pathfinder, *start, *end))
+            }
+            _ => None,
+        };
+        Ok((cell_to_cell, start, end, Departure::new(departure, &routing.travel)))
+    });
+    let (cell_to_cell, start, end, departure) = match prepared {
+        Ok(prepared) => prepared,
+        Err(message) => return (StatusCode::BAD_REQUEST, PathResponse::failure(lang, message, None)),
+    };
 
+    // Chemin sans contrainte horaire, servi par le cache s'il a déjà été calculé sur ces coûts
+    let key = RouteKey {
+        version: routing.version,
+        profile: profile.to_string(),
+        algorithm: cell_to_cell.as_ref().map_or("dijkstra", |(pathfinder, _, _)| pathfinder.name()),
+        start: start.place(),
+        end: end.place(),
+    };
+    let (cells, stats) = state.route_cache.get(&key).unwrap_or_else(|| {
+        let route = match &cell_to_cell {
+            Some((pathfinder, start, end)) => find_optimal_path(pathfinder.as_ref(), &state.map, &routing.costs, *start, *end, budget),
+            None => find_area_path(&state.map, &routing.costs, start.cells(), end.cells(), budget),
+        };
+        state.route_cache.insert(key, route.clone());
+        route
+    });
 
+    // Le meilleur chemin traverse une zone fermée à l'heure de passage : recherche dépendant de l'heure
+    let respects = |cells: &[(usize, usize)]| respects_schedule(&state.map, &state.calendar, &departure, cells);
+    let (cells, stats) = match cells {
+        Some(cells) if stats.aborted.is_none() && !respects(&cells) => {
+            find_timed_path(&state.map, &routing.costs, &state.calendar, &departure, start.cells(), end.cells(), budget)
+        }
+        cells => (cells, stats),
+    };
 
+    match cells.filter(|_| stats.aborted.is_none()) {
+        Some(cells) => {
+            let smooth = query.smooth.unwrap_or(false);
+            let with_cells = query.cells.unwrap_or(false);
 
+            let alternatives = query.alternatives.filter(|&count| count > 0).map(|count| {
+                find_alternatives(&state.map, &routing.costs, &cells, count.min(MAX_ALTERNATIVES), budget)
+                    .into_iter()
+                    .filter(|alternative| respects(alternative))
+                    .map(|alternative| {
+                        let segments = path_segments(&state.map, &routing.costs, &routing.travel, &alternative, smooth, lang);
+                        AlternativeRoute {
+                            total_cost: path_cost(&routing.costs, &alternative),
+                            total_time_s: total_time(&segments),
+                            instructions: generate_instructions(&state.map, &segments, state.meters_per_cell, lang),
+                            segments,
+                            cells: with_cells.then_some(alternative),
+                        }
+                    })
+                    .collect()
+            });
 
+            let segments = path_segments(&state.map, &routing.costs, &routing.travel, &cells, smooth, lang);
+            let total_cost = path_cost(&routing.costs, &cells);
+            let total_time_s = total_time(&segments);
+            let response = PathResponse {
+                success: true,
+                message: lang.message(&Message::PathFound),
+                total_cost: Some(total_cost),
+                total_time_s: Some(total_time_s),
+                departure_time: Some(format_datetime(departure.instant)),
+                arrival_time: Some(format_datetime(departure.arrival(total_time_s))),
+                instructions: Some(generate_instructions(&state.map, &segments, state.meters_per_cell, lang)),
+                segments: Some(segments),
+                cells: with_cells.then_some(cells),
+                alternatives,
+                search: Some(stats),
+            };
+            (StatusCode::OK, response)
+        }
+        None => {
+            let (status, message) = search_failure(&stats, Message::NoPathFound);
+            (status, PathResponse::failure(lang, message, Some(stats)))
+        }
+    }
+}
 
+async fn find_nearest_facility(req: HttpRequest, state: web::Data<AppState>, query: web::Query<NearestQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let budget = SearchBudget::new(state.search_limits);
+    let _cancel = budget.cancel_on_drop();
 
+    let query = query.into_inner();
+    run_search(&state, lang, move |state| route_nearest(state, &query, lang, &budget), |lang, message| {
+        NearestResponse::failure(lang, message, None)
+    })
+    .await
+}
 
+// Recherche de `/navigate/nearest`, exécutée sur le pool de calcul
+fn route_nearest(state: &AppState, query: &NearestQuery, lang: Lang, budget: &SearchBudget) -> (StatusCode, NearestResponse) {
+    let profile = query.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(routing) = state.routing(profile) else {
+        return (StatusCode::BAD_REQUEST, NearestResponse::failure(lang, Message::UnknownProfile(profile.to_string()), None));
+    };
 
-// use actix_web::{web, HttpResponse, Responder};
-// use crate::data::loader::{Map, SurfaceType};
-// use crate::navigation::astar::{astar_pathfinding, PathSegment }; // Correction : PathStep remplacé par PathSegment 
-// use log::{info, warn};
-// use serde::{Serialize, Deserialize};
+    let Some(facility) = Facility::parse(&query.facility) else {
+        return (StatusCode::BAD_REQUEST, NearestResponse::failure(lang, Message::UnknownFacility(query.facility.clone()), None));
+    };
 
-// #[derive(Deserialize)]
-// struct PathQuery {
-//     start_x: usize,
-//     start_y: usize,
-//     end_x: usize,
-//     end_y: usize,
-// }
+    let start = (query.start_x, query.start_y);
+    let (cells, stats) = find_nearest(&state.map, &routing.costs, start, facility, budget);
 
-// #[derive(Serialize)]
-// struct PathResponse {
-//     success: bool,
-//     message: String,
-//     path: Option<Vec<(usize, usize)>>,
-//     details: Option<Vec<CompressedPathStep>>, // Correction : PathStep → CompressedPathStep
-// }
+    match cells.filter(|_| stats.aborted.is_none()) {
+        Some(cells) => {
+            let target = cells[cells.len() - 1];
+            let segments = path_segments(&state.map, &routing.costs, &routing.travel, &cells, query.smooth.unwrap_or(false), lang);
+            let response = NearestResponse {
+                success: true,
+                message: lang.message(&Message::PathFound),
+                target: Some(target),
+                target_surface: Some(lang.surface_name(state.map.get_surface(target.0, target.1))),
+                instructions: Some(generate_instructions(&state.map, &segments, state.meters_per_cell, lang)),
+                segments: Some(segments),
+                cells: query.cells.unwrap_or(false).then_some(cells),
+                search: Some(stats),
+            };
+            (StatusCode::OK, response)
+        }
+        None => {
+            let (status, message) = search_failure(&stats, Message::NoFacilityFound);
+            (status, NearestResponse::failure(lang, message, Some(stats)))
+        }
+    }
+}
 
-// pub fn configure(cfg: &mut web::ServiceConfig) {
-//     cfg.route("/navigate", web::get().to(find_path));
-// }
+// Arrêts de la requête : cases de `stops` puis centre de chaque salle de `rooms`
+fn parse_stops(state: &AppState, query: &TourQuery) -> Result<Vec<(usize, usize)>, Message> {
+    let mut stops = vec![];
 
-// async fn find_path(query: web::Query<PathQuery>) -> impl Responder {
-//     let PathQuery { start_x, start_y, end_x, end_y } = query.into_inner();
-//     info!("🛰️ Requête reçue : start=({}, {}), end=({}, {})", start_x, start_y, end_x, end_y);
+    if let Some(value) = query.stops.as_deref().filter(|value| !value.is_empty()) {
+        for stop in value.split(';') {
+            let cell = stop
+                .split_once(',')
+                .and_then(|(x, y)| Some((x.trim().parse().ok()?, y.trim().parse().ok()?)))
+                .filter(|&(x, y)| state.map.contains(x, y))
+                .ok_or_else(|| Message::InvalidStops(stop.to_string()))?;
+            stops.push(cell);
+        }
+    }
 
-//     let map = Map::load_from_file("data/SurfaceInfo.txt");
+    if let Some(value) = query.rooms.as_deref().filter(|value| !value.is_empty()) {
+        for room in value.split(',') {
+            let id: usize = room.trim().parse().map_err(|_| Message::InvalidStops(room.to_string()))?;
+            stops.push(state.map.room_center(id).ok_or(Message::UnknownRoom(id))?);
+        }
+    }
 
-//     let start_surface = map.get_surface(start_x, start_y);
-//     let end_surface = map.get_surface(end_x, end_y);
+    match stops.len() {
+        0 => Err(Message::NoStops),
+        n if n > MAX_STOPS => Err(Message::TooManyStops(MAX_STOPS)),
+        _ => Ok(stops),
+    }
+}
+
+async fn find_tour(req: HttpRequest, state: web::Data<AppState>, query: web::Query<TourQuery>) -> impl Responder {
+    let lang = request_lang(&req, query.lang.as_deref());
+    let budget = SearchBudget::new(state.search_limits);
+    let _cancel = budget.cancel_on_drop();
+
+    let query = query.into_inner();
+    run_search(&state, lang, move |state| route_tour(state, &query, lang, &budget), |lang, message| {
+        TourResponse::failure(lang, message, None)
+    })
+    .await
+}
 
-//     if matches!(start_surface, SurfaceType::Mur) || matches!(end_surface, SurfaceType::Mur) {
-//         warn!("❌ Départ ou arrivée sur un mur !");
-//         return HttpResponse::BadRequest().json(PathResponse {
-//             success: false,
-//             message: "Le point de départ ou d'arrivée est un mur.".to_string(),
-//             path: None,
-//             details: None, 
-//         });
-//     }
+// Recherche de `/navigate/tour`, exécutée sur le pool de calcul
+fn route_tour(state: &AppState, query: &TourQuery, lang: Lang, budget: &SearchBudget) -> (StatusCode, TourResponse) {
+    let profile = query.profile.as_deref().unwrap_or(DEFAULT_PROFILE);
+    let Some(routing) = state.routing(profile) else {
+        return (StatusCode::BAD_REQUEST, TourResponse::failure(lang, Message::UnknownProfile(profile.to_string()), None));
+    };
 
-//     match astar_pathfinding(&map, (start_x, start_y), (end_x, end_y)) {
-//         Some(full_path) => {
-//             let simple_path: Vec<(usize, usize)> = full_path.iter().map(|step| step.start).collect(); // Correction : coordinates → start
-//             info!("✅ Chemin trouvé avec {} étapes", full_path.len());
+    let start = (query.start_x, query.start_y);
+    let stops = if state.map.contains(start.0, start.1) { parse_stops(state, query) } else { Err(Message::OutsideMap(start)) };
+    let stops = match stops {
+        Ok(stops) => stops,
+        Err(message) => return (StatusCode::BAD_REQUEST, TourResponse::failure(lang, message, None)),
+    };
 
-//             HttpResponse::Ok().json(PathResponse {
-//                 success: true,
-//                 message: "Chemin trouvé avec succès".to_string(),
-//                 path: Some(simple_path),
-//                 details: Some(full_path),
-//             })
-//         },
-//         None => {
-//             warn!("❌ Aucun chemin trouvé !");
-//             HttpResponse::NotFound().json(PathResponse {
-//                 success: false,
-//                 message: "Aucun chemin trouvé. Vérifiez que le chemin est possible.".to_string(),
-//                 path: None,
-//                 details: None,
-//             })
-//         }
-//     }
-// }
+    let return_to_start = query.return_to_start.unwrap_or(false);
+    let (tour, stats) = plan_tour(&state.map, &routing.costs, start, &stops, return_to_start, budget);
+
+    match tour {
+        _ if stats.aborted.is_some() => {
+            let (status, message) = search_failure(&stats, Message::NoPathFound);
+            (status, TourResponse::failure(lang, message, Some(stats)))
+        }
+        Ok(tour) => {
+            let mut segments = vec![];
+            let mut instructions = vec![];
+            for (index, leg) in tour.legs.iter().enumerate() {
+                let leg_segments = path_segments(&state.map, &routing.costs, &routing.travel, leg, query.smooth.unwrap_or(false), lang);
+                // Chaque étape reprend là où la précédente est arrivée : un seul départ
+                let leg_instructions = generate_instructions(&state.map, &leg_segments, state.meters_per_cell, lang);
+                instructions.extend(leg_instructions.into_iter().skip(usize::from(index > 0)));
+                segments.extend(leg_segments);
+            }
+
+            let response = TourResponse {
+                success: true,
+                message: lang.message(&Message::TourFound),
+                order: Some(tour.order.iter().map(|&stop| stops[stop]).collect()),
+                total_cost: Some(tour.total_cost),
+                total_time_s: Some(total_time(&segments)),
+                segments: Some(segments),
+                instructions: Some(instructions),
+                search: Some(stats),
+            };
+            (StatusCode::OK, response)
+        }
+        Err(message) => (StatusCode::NOT_FOUND, TourResponse::failure(lang, message, Some(stats))),
+    }
+}
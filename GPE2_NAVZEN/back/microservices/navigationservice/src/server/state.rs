@@ -0,0 +1,350 @@
+use std::collections::BTreeMap;
+use std::panic::{catch_unwind, AssertUnwindSafe};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::mpsc::{self, Sender};
+use std::sync::{Arc, Mutex, MutexGuard, RwLock};
+use std::thread;
+use crate::data::closures::{now, Closure, ClosureArea, ClosureStore};
+use crate::data::exits::{load_exits, Exit};
+use crate::data::loader::{Map, SurfaceType};
+use crate::data::schedules::Calendar;
+use crate::navigation::budget::SearchLimits;
+use crate::navigation::distances::DistanceMatrix;
+use crate::navigation::evacuation::{FlowField, HAZARD_PENALTY};
+use crate::navigation::hierarchy::RegionGraph;
+use crate::navigation::landmarks::Landmarks;
+use crate::navigation::profile::{load_profiles, CostGrid};
+use crate::navigation::travel_time::TravelTime;
+use crate::services::compute::ComputePool;
+use crate::services::route_cache::RouteCache;
+use crate::utils::clock::Clock;
+use log::{error, info};
+
+/// Fichiers de données lus au démarrage
+pub struct DataFiles {
+    pub surface_info: String,
+    pub profiles: String,
+    pub closures: String,  // Fermetures enregistrées par l'API
+    pub hazards: String,   // Zones dangereuses déclarées pendant une évacuation
+    pub schedules: String, // Horaires d'ouverture
+    pub exits: String,     // Sorties de secours désignées
+}
+
+// Coûts et prétraitements propres à un profil d'itinéraire, fermetures et dangers en vigueur compris
+pub struct RoutingData {
+    pub version: u64, // Change à chaque mise à jour des fermetures ou des dangers
+    pub costs: CostGrid,
+    pub regions: RegionGraph,
+    pub landmarks: Option<Arc<Landmarks>>, // Prétraitement ALT, désactivé si aucun repère demandé
+    pub evacuation: FlowField,              // Direction de la sortie la plus proche depuis chaque case
+    pub travel: TravelTime,                 // Vitesses du profil, pour les durées de parcours
+    pub hazards: Vec<Closure>,              // Zones dangereuses prises en compte par ces coûts et ce champ
+    distances: RwLock<Arc<DistanceMatrix>>, // Distances entre salles, recalculées en arrière-plan après les coûts
+}
+
+impl RoutingData {
+    /// Distances entre salles : celles de ces coûts, ou les précédentes tant que leur recalcul
+    /// n'est pas terminé (à comparer `DistanceMatrix::version` à `version`)
+    pub fn distances(&self) -> Arc<DistanceMatrix> {
+        self.distances.read().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+}
+
+// Données d'itinéraire de chaque profil, partagées avec le thread qui les recalcule
+struct RoutingTable {
+    map: Arc<Map>,
+    exits: Arc<Vec<Exit>>,
+    base_costs: BTreeMap<String, CostGrid>, // Coûts de chaque profil sans les fermetures
+    profiles: RwLock<BTreeMap<String, Arc<RoutingData>>>,
+    version: AtomicU64,
+    requested: AtomicU64, // Numéro du dernier recalcul demandé
+    applied: AtomicU64,   // Numéro du dernier recalcul en service
+}
+
+// Recalcul demandé au thread de fond : restrictions en vigueur
+struct Rebuild {
+    number: u64,
+    closures: Vec<Closure>,
+    hazards: Vec<Closure>,
+}
+
+// État partagé entre les workers : la carte et ses prétraitements sont chargés une seule fois
+pub struct AppState {
+    pub map: Arc<Map>,
+    pub meters_per_cell: f32,  // Échelle de la carte, pour les distances des instructions
+    pub calendar: Calendar,    // Horaires d'ouverture des zones
+    pub clock: Box<dyn Clock>, // Heure locale (figée par FIXED_CLOCK pour les tests)
+    pub search_limits: SearchLimits, // Limites de chaque recherche (nœuds, durée)
+    pub compute: ComputePool,  // Threads des recherches, hors des workers actix
+    pub route_cache: RouteCache, // Itinéraires déjà calculés, par version des coûts
+    pub exits: Arc<Vec<Exit>>, // Sorties de secours désignées (en plus des cases extérieures)
+    routing: Arc<RoutingTable>,
+    rebuilds: Sender<Rebuild>, // Demandes de recalcul des coûts, traitées hors des requêtes
+    closures: Mutex<ClosureStore>,
+    hazards: Mutex<ClosureStore>,
+    next_expiry: AtomicU64, // Prochaine expiration d'une fermeture ou d'un danger (u64::MAX si aucune)
+}
+
+impl AppState {
+    /// Charge la carte, les profils, les fermetures, les dangers, les horaires et les sorties,
+    /// puis précalcule pour chaque profil le graphe de régions, les repères ALT et le champ d'évacuation
+    pub fn load(
+        files: &DataFiles,
+        landmark_count: usize,
+        meters_per_cell: f32,
+        search_limits: SearchLimits,
+        compute: ComputePool,
+        route_cache: RouteCache,
+        clock: Box<dyn Clock>,
+    ) -> Self {
+        let map = Arc::new(Map::load_from_file(&files.surface_info));
+        let closures = ClosureStore::load(&files.closures, "fermeture");
+        let hazards = ClosureStore::load(&files.hazards, "zone dangereuse");
+        let calendar = Calendar::load(&files.schedules, &map);
+        let exits = Arc::new(load_exits(&files.exits, &map));
+        // Les versions partent de l'heure de démarrage : un ETag ne resert pas après un redémarrage
+        let version = now();
+
+        let mut base_costs = BTreeMap::new();
+        let mut profiles = BTreeMap::new();
+        for (name, profile) in load_profiles(&files.profiles) {
+            info!("🧭 Préparation du profil `{}`", name);
+            let costs = CostGrid::build(&map, &profile);
+            // Les fermetures ne font qu'augmenter les coûts : les bornes ALT calculées sans elles restent admissibles
+            let landmarks = (landmark_count > 0).then(|| Arc::new(Landmarks::build(&map, &costs, landmark_count)));
+            let travel = TravelTime::new(&profile, meters_per_cell);
+            let restrictions = Restrictions { closures: closures.list(), hazards: hazards.list(), exits: &exits };
+            profiles.insert(name.clone(), Arc::new(routing_data(&map, &costs, &restrictions, landmarks, travel, version, None)));
+            base_costs.insert(name, costs);
+        }
+
+        let next_expiry = AtomicU64::new(next_expiry(&closures, &hazards));
+        let routing = Arc::new(RoutingTable {
+            map: map.clone(),
+            exits: exits.clone(),
+            base_costs,
+            profiles: RwLock::new(profiles),
+            version: AtomicU64::new(version),
+            requested: AtomicU64::new(0),
+            applied: AtomicU64::new(0),
+        });
+        Self {
+            map,
+            meters_per_cell,
+            calendar,
+            clock,
+            search_limits,
+            compute,
+            route_cache,
+            exits,
+            rebuilds: spawn_rebuilds(routing.clone()),
+            routing,
+            closures: Mutex::new(closures),
+            hazards: Mutex::new(hazards),
+            next_expiry,
+        }
+    }
+
+    /// Données d'itinéraire du profil demandé. Après une modification des fermetures ou des
+    /// dangers (ou une expiration), ce sont les données précédentes jusqu'à la fin du recalcul.
+    pub fn routing(&self, profile: &str) -> Option<Arc<RoutingData>> {
+        if now() >= self.next_expiry.load(Ordering::Relaxed) {
+            drop(self.stores());
+        }
+
+        self.routing.profiles.read().unwrap_or_else(|e| e.into_inner()).get(profile).cloned()
+    }
+
+    /// Indique si des fermetures ou des dangers ont changé depuis les données en service
+    /// (recalcul en cours ou en attente)
+    pub fn rebuild_pending(&self) -> bool {
+        self.routing.applied.load(Ordering::Acquire) < self.routing.requested.load(Ordering::Acquire)
+    }
+
+    /// Fermetures en vigueur
+    pub fn closures(&self) -> Vec<Closure> {
+        self.stores().0.list().to_vec()
+    }
+
+    /// Enregistre une fermeture ; les coûts de tous les profils sont recalculés en arrière-plan
+    pub fn add_closure(&self, area: ClosureArea, reason: Option<String>, expires_at: Option<u64>, penalty: Option<f32>) -> Closure {
+        let (mut closures, hazards) = self.stores();
+        let closure = closures.add(area, reason, expires_at, penalty);
+        self.schedule_rebuild(&closures, &hazards);
+        closure
+    }
+
+    /// Supprime une fermeture (coûts recalculés en arrière-plan) ; false si elle n'existe pas
+    pub fn remove_closure(&self, id: u64) -> bool {
+        let (mut closures, hazards) = self.stores();
+        let removed = closures.remove(id);
+        if removed {
+            self.schedule_rebuild(&closures, &hazards);
+        }
+        removed
+    }
+
+    /// Zones dangereuses en vigueur
+    pub fn hazards(&self) -> Vec<Closure> {
+        self.stores().1.list().to_vec()
+    }
+
+    /// Déclare une zone dangereuse : elle devient infranchissable pour les itinéraires, et les
+    /// champs d'évacuation de tous les profils sont recalculés en arrière-plan (nouvelle version
+    /// du champ, donc nouvel ETag, une fois le recalcul terminé)
+    pub fn add_hazard(&self, area: ClosureArea, reason: Option<String>, expires_at: Option<u64>) -> Closure {
+        let (closures, mut hazards) = self.stores();
+        let hazard = hazards.add(area, reason, expires_at, None);
+        self.schedule_rebuild(&closures, &hazards);
+        hazard
+    }
+
+    /// Lève une zone dangereuse (champs recalculés en arrière-plan) ; false si elle n'existe pas
+    pub fn remove_hazard(&self, id: u64) -> bool {
+        let (closures, mut hazards) = self.stores();
+        let removed = hazards.remove(id);
+        if removed {
+            self.schedule_rebuild(&closures, &hazards);
+        }
+        removed
+    }
+
+    // Verrouille les fermetures puis les dangers (toujours dans cet ordre), après avoir retiré
+    // ceux qui ont expiré
+    fn stores(&self) -> (MutexGuard<'_, ClosureStore>, MutexGuard<'_, ClosureStore>) {
+        let mut closures = self.closures.lock().unwrap_or_else(|e| e.into_inner());
+        let mut hazards = self.hazards.lock().unwrap_or_else(|e| e.into_inner());
+
+        let now = now();
+        if closures.purge_expired(now) | hazards.purge_expired(now) {
+            self.schedule_rebuild(&closures, &hazards);
+        }
+        (closures, hazards)
+    }
+
+    // Confie le recalcul des coûts au thread de fond, sans attendre. Appelé avec les deux
+    // registres verrouillés : les demandes arrivent dans l'ordre des modifications, et la
+    // dernière l'emporte.
+    fn schedule_rebuild(&self, closures: &ClosureStore, hazards: &ClosureStore) {
+        self.next_expiry.store(next_expiry(closures, hazards), Ordering::Relaxed);
+
+        let number = self.routing.requested.fetch_add(1, Ordering::AcqRel) + 1;
+        let rebuild = Rebuild { number, closures: closures.list().to_vec(), hazards: hazards.list().to_vec() };
+        if self.rebuilds.send(rebuild).is_err() {
+            error!("❌ Le thread de recalcul des coûts est arrêté : fermetures et dangers ne sont plus appliqués");
+        }
+    }
+}
+
+impl RoutingTable {
+    // Recalcule les coûts, le graphe de régions et le champ d'évacuation de chaque profil à partir
+    // des coûts de base, sans verrou, puis remplace toutes les données d'un coup. Les distances
+    // entre salles, plus longues à calculer, suivent une fois les nouveaux coûts en service.
+    fn rebuild(&self, number: u64, closures: &[Closure], hazards: &[Closure]) {
+        let version = self.version.fetch_add(1, Ordering::Relaxed) + 1;
+        let restrictions = Restrictions { closures, hazards, exits: &self.exits };
+        let previous = self.profiles.read().unwrap_or_else(|e| e.into_inner()).clone();
+
+        let rebuilt = self
+            .base_costs
+            .iter()
+            .filter_map(|(name, costs)| {
+                // Repères ALT et vitesses ne dépendent pas des restrictions : repris du calcul précédent
+                let previous = previous.get(name)?;
+                let (landmarks, travel) = (previous.landmarks.clone(), previous.travel);
+                let distances = Some(previous.distances());
+                Some((name.clone(), Arc::new(routing_data(&self.map, costs, &restrictions, landmarks, travel, version, distances))))
+            })
+            .collect::<BTreeMap<_, _>>();
+        *self.profiles.write().unwrap_or_else(|e| e.into_inner()) = rebuilt.clone();
+        self.applied.store(number, Ordering::Release);
+
+        info!("🚧 Coûts mis à jour : {} fermeture(s) et {} zone(s) dangereuse(s) en vigueur", closures.len(), hazards.len());
+
+        // En attendant, `/distances` sert les distances précédentes, marquées comme périmées
+        for routing in rebuilt.values() {
+            let matrix = DistanceMatrix::build(&self.map, &routing.costs, &routing.travel, routing.version);
+            *routing.distances.write().unwrap_or_else(|e| e.into_inner()) = Arc::new(matrix);
+        }
+    }
+}
+
+// Thread de recalcul des coûts : traite les demandes une à une ; celles arrivées pendant un
+// recalcul sont regroupées et seule la plus récente est appliquée
+fn spawn_rebuilds(routing: Arc<RoutingTable>) -> Sender<Rebuild> {
+    let (sender, receiver) = mpsc::channel::<Rebuild>();
+
+    thread::Builder::new()
+        .name("recalcul-couts".to_string())
+        .spawn(move || {
+            while let Ok(mut rebuild) = receiver.recv() {
+                // Seules les restrictions les plus récentes comptent
+                while let Ok(next) = receiver.try_recv() {
+                    rebuild = next;
+                }
+
+                if catch_unwind(AssertUnwindSafe(|| routing.rebuild(rebuild.number, &rebuild.closures, &rebuild.hazards))).is_err() {
+                    error!("❌ Le recalcul des coûts a paniqué : les données précédentes restent en service");
+                }
+            }
+        })
+        .expect("Impossible de lancer le thread de recalcul des coûts");
+
+    sender
+}
+
+// Restrictions à appliquer aux coûts de base d'un profil
+struct Restrictions<'a> {
+    closures: &'a [Closure],
+    hazards: &'a [Closure],
+    exits: &'a [Exit],
+}
+
+fn next_expiry(closures: &ClosureStore, hazards: &ClosureStore) -> u64 {
+    closures.next_expiry().into_iter().chain(hazards.next_expiry()).min().unwrap_or(u64::MAX)
+}
+
+// Applique les fermetures et les dangers aux coûts de base d'un profil, puis reconstruit son
+// graphe de régions et son champ d'évacuation ; les distances entre salles sont reprises de
+// `distances` si fournies (recalculées ensuite en arrière-plan), calculées ici sinon
+fn routing_data(
+    map: &Map,
+    base_costs: &CostGrid,
+    restrictions: &Restrictions,
+    landmarks: Option<Arc<Landmarks>>,
+    travel: TravelTime,
+    version: u64,
+    distances: Option<Arc<DistanceMatrix>>,
+) -> RoutingData {
+    let mut costs = base_costs.clone();
+    for closure in restrictions.closures {
+        let cells = closure.area.cells(map);
+        match closure.penalty {
+            Some(factor) => costs.penalize(cells, factor),
+            None => costs.close(cells),
+        }
+    }
+
+    // Sorties sûres : cases extérieures et sorties désignées, hors zones dangereuses
+    let mut in_hazard = vec![false; map.width * map.height];
+    for (x, y) in restrictions.hazards.iter().flat_map(|hazard| hazard.area.cells(map)) {
+        in_hazard[map.index(x, y)] = true;
+    }
+    let hazard_cells: Vec<(usize, usize)> = (0..in_hazard.len()).filter(|&i| in_hazard[i]).map(|i| map.position(i)).collect();
+    let exits: Vec<(usize, usize)> = (0..map.width * map.height)
+        .map(|index| map.position(index))
+        .filter(|&(x, y)| map.get_surface(x, y) == SurfaceType::Exterieur)
+        .chain(restrictions.exits.iter().flat_map(|exit| exit.cells.iter().copied()))
+        .filter(|&(x, y)| !in_hazard[map.index(x, y)])
+        .collect();
+
+    let mut evacuation_costs = costs.clone();
+    evacuation_costs.penalize(hazard_cells.iter().copied(), HAZARD_PENALTY);
+    let evacuation = FlowField::build(map, &evacuation_costs, &travel, &exits);
+
+    costs.close(hazard_cells);
+    let regions = RegionGraph::build(map, &costs);
+    let distances = distances.unwrap_or_else(|| Arc::new(DistanceMatrix::build(map, &costs, &travel, version)));
+    let hazards = restrictions.hazards.to_vec();
+    RoutingData { version, costs, regions, landmarks, evacuation, travel, hazards, distances: RwLock::new(distances) }
+}
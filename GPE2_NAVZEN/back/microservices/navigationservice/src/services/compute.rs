@@ -0,0 +1,124 @@
+// Pool de threads dédié aux recherches d'itinéraire.
+//
+// Les handlers actix sont asynchrones : une recherche exécutée directement dans le handler
+// bloquerait le worker et toutes les requêtes qu'il sert (santé, fermetures...). Les recherches
+// sont donc confiées à ce pool, dont la file est bornée : au-delà, la requête est refusée tout
+// de suite plutôt que d'attendre derrière des recherches qui finiraient par expirer.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use log::{error, info, warn};
+use rayon::{ThreadPool, ThreadPoolBuilder};
+use tokio::sync::oneshot;
+
+/// Échec d'un calcul confié au pool
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ComputeError {
+    Busy,     // File pleine : la requête n'a pas été lancée
+    Panicked, // Le calcul a paniqué
+}
+
+/// Pool de calcul : threads rayon et nombre de calculs en attente ou en cours
+pub struct ComputePool {
+    pool: ThreadPool,
+    pending: Arc<AtomicUsize>,
+    max_pending: usize,
+}
+
+// Libère la place d'un calcul dans la file, même s'il panique
+struct PendingSlot(Arc<AtomicUsize>);
+
+impl Drop for PendingSlot {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::Relaxed);
+    }
+}
+
+impl ComputePool {
+    /// `threads` : 0 pour un thread par cœur ; `max_pending` : calculs en attente ou en cours au plus
+    pub fn new(threads: usize, max_pending: usize) -> Self {
+        let pool = ThreadPoolBuilder::new()
+            .num_threads(threads)
+            .thread_name(|index| format!("recherche-{}", index))
+            // Sans gestionnaire, rayon arrête le processus quand une tâche panique
+            .panic_handler(|_| error!("❌ Une recherche a paniqué"))
+            .build()
+            .expect("Impossible de créer le pool de calcul");
+
+        info!("🧵 Pool de calcul : {} threads, {} recherches en file au plus", pool.current_num_threads(), max_pending);
+        Self { pool, pending: Arc::new(AtomicUsize::new(0)), max_pending: max_pending.max(1) }
+    }
+
+    /// Exécute `job` sur le pool et attend son résultat sans bloquer le worker actix ;
+    /// refuse immédiatement si la file est pleine
+    pub async fn run<R: Send + 'static>(&self, job: impl FnOnce() -> R + Send + 'static) -> Result<R, ComputeError> {
+        if self.pending.fetch_add(1, Ordering::Relaxed) >= self.max_pending {
+            self.pending.fetch_sub(1, Ordering::Relaxed);
+            warn!("🚦 File de calcul pleine ({} recherches), requête refusée", self.max_pending);
+            return Err(ComputeError::Busy);
+        }
+
+        let slot = PendingSlot(self.pending.clone());
+        let (sender, receiver) = oneshot::channel();
+        self.pool.spawn(move || {
+            // Place rendue avant l'envoi du résultat (ou pendant la panique, avant la fermeture du
+            // canal) : le handler qui reçoit la réponse voit la file déjà libérée
+            let slot = slot;
+            let result = job();
+            drop(slot);
+            // Le handler a pu être abandonné entre-temps : le résultat est alors perdu
+            let _ = sender.send(result);
+        });
+
+        // Canal fermé sans résultat : la tâche a paniqué
+        receiver.await.map_err(|_| ComputeError::Panicked)
+    }
+
+    /// Calculs en attente ou en cours
+    pub fn pending(&self) -> usize {
+        self.pending.load(Ordering::Relaxed)
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.max_pending
+    }
+
+    pub fn threads(&self) -> usize {
+        self.pool.current_num_threads()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::mpsc;
+    use std::time::Duration;
+
+    #[tokio::test]
+    async fn full_queue_rejects_and_slots_are_released() {
+        let pool = Arc::new(ComputePool::new(1, 1));
+
+        // Un calcul bloqué occupe la seule place de la file
+        let (release, blocked) = mpsc::channel::<()>();
+        let running = tokio::spawn({
+            let pool = pool.clone();
+            async move { pool.run(move || blocked.recv().map(|_| 42)).await }
+        });
+        while pool.pending() == 0 {
+            tokio::time::sleep(Duration::from_millis(1)).await;
+        }
+
+        assert_eq!(pool.run(|| 0).await, Err(ComputeError::Busy));
+        assert_eq!(pool.pending(), 1);
+
+        release.send(()).unwrap();
+        assert_eq!(running.await.unwrap(), Ok(Ok(42)));
+        assert_eq!(pool.pending(), 0);
+
+        // Une tâche qui panique rend aussi sa place
+        assert_eq!(pool.run(|| -> usize { panic!("recherche en échec") }).await, Err(ComputeError::Panicked));
+        assert_eq!(pool.pending(), 0);
+        assert_eq!(pool.run(|| 7).await, Ok(7));
+        assert_eq!(pool.pending(), 0);
+    }
+}
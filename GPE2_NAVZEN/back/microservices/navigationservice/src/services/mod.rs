@@ -1,2 +1,4 @@
+pub mod compute;
 pub mod pathfinding;
+pub mod route_cache;
 pub mod location_service;
@@ -1,17 +1,313 @@
+use std::collections::HashMap;
+use std::time::Instant;
 use crate::data::loader::Map;
-use crate::navigation::astar::{astar_pathfinding, PathSegment};
+use crate::data::schedules::Calendar;
+use crate::navigation::astar::{astar_pathfinding, dijkstra_pathfinding, path_cost, reconstruct_path, PathSegment};
+use crate::navigation::alternatives::alternative_paths;
+use crate::navigation::bidirectional::bidirectional_pathfinding;
+use crate::navigation::budget::{with_budget, AbortReason, SearchBudget};
+use crate::navigation::buffers::expanded_nodes;
+use crate::navigation::hierarchy::{hierarchical_pathfinding, RegionGraph};
+use crate::navigation::jps::jps_pathfinding;
+use crate::navigation::isochrone::{reachable_cells, Limit, Reached};
+use crate::navigation::landmarks::{alt_pathfinding, Landmarks};
+use crate::navigation::nearest::{area_pathfinding, nearest_pathfinding, paths_to_targets, Facility};
+use crate::navigation::profile::CostGrid;
+use crate::navigation::smoothing::{smooth_path, waypoint_segments};
+use crate::navigation::timed::{timed_pathfinding, Departure};
+use crate::navigation::tour::visiting_order;
+use crate::navigation::travel_time::TravelTime;
+use crate::utils::i18n::{Lang, Message};
+use log::info;
+use serde::Serialize;
 
-pub fn find_optimal_path(map: &Map, start: (usize, usize), end: (usize, usize)) -> Option<Vec<PathSegment >> {
-    astar_pathfinding(map, start, end)
+/// Algorithme de recherche de chemin interchangeable
+pub trait Pathfinder {
+    /// Nom de l'algorithme, tel qu'accepté par le paramètre `algorithm` de `/navigate`
+    fn name(&self) -> &'static str;
+
+    /// Suite de cases voisines du départ à l'arrivée, None si aucun chemin n'existe
+    fn find_path(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>>;
+
+    /// Borne inférieure du coût optimal, pour les algorithmes approchés seulement (None : chemin optimal)
+    fn lower_bound(&self, _map: &Map, _costs: &CostGrid, _start: (usize, usize), _end: (usize, usize)) -> Option<f32> {
+        None
+    }
+}
+
+pub struct AStar;
+pub struct Dijkstra;
+pub struct JumpPointSearch;
+pub struct Bidirectional;
+pub struct Hierarchical<'a>(pub &'a RegionGraph, pub Option<&'a Landmarks>); // Repères : borne du surcoût plus fine
+pub struct Alt<'a>(pub &'a Landmarks);
+
+impl Pathfinder for AStar {
+    fn name(&self) -> &'static str { "astar" }
+
+    fn find_path(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        astar_pathfinding(map, costs, start, end)
+    }
+}
+
+impl Pathfinder for Dijkstra {
+    fn name(&self) -> &'static str { "dijkstra" }
+
+    fn find_path(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        dijkstra_pathfinding(map, costs, start, end)
+    }
+}
+
+impl Pathfinder for JumpPointSearch {
+    fn name(&self) -> &'static str { "jps" }
+
+    fn find_path(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        jps_pathfinding(map, costs, start, end)
+    }
+}
+
+impl Pathfinder for Bidirectional {
+    fn name(&self) -> &'static str { "bidirectional" }
+
+    fn find_path(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        bidirectional_pathfinding(map, costs, start, end)
+    }
+}
+
+impl Pathfinder for Hierarchical<'_> {
+    fn name(&self) -> &'static str { "hpa" }
+
+    fn find_path(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        hierarchical_pathfinding(map, costs, self.0, start, end)
+    }
+
+    // Heuristique octile, ou bornes ALT si des repères sont disponibles pour le profil
+    fn lower_bound(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<f32> {
+        let octile = costs.estimate(start, end);
+        Some(self.1.map_or(octile, |landmarks| {
+            landmarks.lower_bound(map.index(start.0, start.1), map.index(end.0, end.1)).max(octile)
+        }))
+    }
+}
+
+impl Pathfinder for Alt<'_> {
+    fn name(&self) -> &'static str { "alt" }
+
+    fn find_path(&self, map: &Map, costs: &CostGrid, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>> {
+        alt_pathfinding(map, costs, self.0, start, end)
+    }
+}
+
+/// Choisit l'algorithme demandé (A* par défaut) avec les prétraitements du profil choisi ;
+/// retourne le message d'erreur à renvoyer s'il est inconnu ou indisponible
+pub fn select_pathfinder<'a>(
+    name: Option<&str>,
+    regions: &'a RegionGraph,
+    landmarks: Option<&'a Landmarks>,
+) -> Result<Box<dyn Pathfinder + 'a>, Message> {
+    match name {
+        None | Some("astar") => Ok(Box::new(AStar)),
+        Some("dijkstra") => Ok(Box::new(Dijkstra)),
+        Some("jps") => Ok(Box::new(JumpPointSearch)),
+        Some("bidirectional") => Ok(Box::new(Bidirectional)),
+        Some("hpa") => Ok(Box::new(Hierarchical(regions, landmarks))),
+        Some("alt") => landmarks
+            .map(|landmarks| Box::new(Alt(landmarks)) as Box<dyn Pathfinder>)
+            .ok_or(Message::LandmarksUnavailable),
+        Some(other) => Err(Message::UnknownAlgorithm(other.to_string())),
+    }
+}
+
+/// Mesures d'une recherche, renvoyées au client pour comparer les algorithmes
+#[derive(Debug, Clone, Serialize)]
+pub struct SearchStats {
+    pub algorithm: &'static str,
+    pub expanded_nodes: usize,
+    pub duration_ms: f64,
+    pub aborted: Option<AbortReason>, // Recherche interrompue : le résultat n'est pas fiable
+    pub cached: bool,                 // Itinéraire servi par le cache (mesures de la recherche d'origine)
+    pub max_extra_cost: Option<f32>,  // Algorithme approché : surcoût maximal du chemin par rapport au chemin optimal
 }
 
+// Exécute une recherche sous le budget de la requête en mesurant les nœuds développés et la durée
+fn measure<T>(algorithm: &'static str, budget: &SearchBudget, search: impl FnOnce() -> T) -> (T, SearchStats) {
+    let expanded_before = expanded_nodes();
+    let started = Instant::now();
 
+    let (result, aborted) = with_budget(budget, search);
 
-// use crate::data::loader::Map;
-// use crate::navigation::astar::{astar_pathfinding, PathSegment }; // Correction : PathStep → PathSegment 
+    let stats = SearchStats {
+        algorithm,
+        expanded_nodes: expanded_nodes() - expanded_before,
+        duration_ms: started.elapsed().as_secs_f64() * 1000.0,
+        aborted,
+        cached: false,
+        max_extra_cost: None,
+    };
+    info!("📊 {} : {} nœuds développés en {:.2} ms", stats.algorithm, stats.expanded_nodes, stats.duration_ms);
 
-// pub fn find_optimal_path(map: &Map, start: (usize, usize), end: (usize, usize)) -> Option<Vec<(usize, usize)>> {
-//     astar_pathfinding(map, start, end).map(|path| {
-//         path.into_iter().map(|step| step.start).collect() // Correction : coordinates → start
-//     })
-// }
+    (result, stats)
+}
+
+pub fn find_optimal_path(
+    pathfinder: &dyn Pathfinder,
+    map: &Map,
+    costs: &CostGrid,
+    start: (usize, usize),
+    end: (usize, usize),
+    budget: &SearchBudget,
+) -> (Option<Vec<(usize, usize)>>, SearchStats) {
+    let (cells, mut stats) = measure(pathfinder.name(), budget, || pathfinder.find_path(map, costs, start, end));
+
+    if let (Some(cells), Some(bound)) = (&cells, pathfinder.lower_bound(map, costs, start, end)) {
+        stats.max_extra_cost = Some((path_cost(costs, cells) - bound).max(0.0));
+    }
+    (cells, stats)
+}
+
+/// Chemin vers le lieu du type demandé le moins coûteux à atteindre (Dijkstra à cibles multiples)
+pub fn find_nearest(
+    map: &Map,
+    costs: &CostGrid,
+    start: (usize, usize),
+    facility: Facility,
+    budget: &SearchBudget,
+) -> (Option<Vec<(usize, usize)>>, SearchStats) {
+    measure("dijkstra", budget, || nearest_pathfinding(map, costs, start, facility))
+}
+
+/// Chemin le moins coûteux d'une case de `sources` à une case de `targets` (une salle entière
+/// au départ ou à l'arrivée) : Dijkstra à sources et cibles multiples
+pub fn find_area_path(
+    map: &Map,
+    costs: &CostGrid,
+    sources: &[(usize, usize)],
+    targets: &[(usize, usize)],
+    budget: &SearchBudget,
+) -> (Option<Vec<(usize, usize)>>, SearchStats) {
+    measure("dijkstra", budget, || area_pathfinding(map, costs, sources, targets))
+}
+
+/// Cases accessibles depuis `sources` dans la limite de longueur ou de durée `limit` (Dijkstra vers toutes les cases)
+pub fn find_reachable(
+    map: &Map,
+    costs: &CostGrid,
+    travel: &TravelTime,
+    sources: &[(usize, usize)],
+    limit: Limit,
+    budget: &SearchBudget,
+) -> (Vec<Reached>, SearchStats) {
+    measure("dijkstra", budget, || reachable_cells(map, costs, travel, sources, limit))
+}
+
+/// Chemin qui ne traverse que des zones ouvertes à l'heure de passage (voir `navigation::timed`)
+pub fn find_timed_path(
+    map: &Map,
+    costs: &CostGrid,
+    calendar: &Calendar,
+    departure: &Departure,
+    sources: &[(usize, usize)],
+    targets: &[(usize, usize)],
+    budget: &SearchBudget,
+) -> (Option<Vec<(usize, usize)>>, SearchStats) {
+    measure("timed", budget, || timed_pathfinding(map, costs, calendar, departure, sources, targets))
+}
+
+/// Itinéraires alternatifs au meilleur chemin (voir `navigation::alternatives`) ; si le budget
+/// s'épuise, seules les alternatives déjà trouvées sont renvoyées
+pub fn find_alternatives(
+    map: &Map,
+    costs: &CostGrid,
+    best: &[(usize, usize)],
+    count: usize,
+    budget: &SearchBudget,
+) -> Vec<Vec<(usize, usize)>> {
+    with_budget(budget, || alternative_paths(map, costs, best, count)).0
+}
+
+/// Découpe un chemin en segments ; `smooth` le remplace d'abord par une polyligne any-angle
+pub fn path_segments(
+    map: &Map,
+    costs: &CostGrid,
+    travel: &TravelTime,
+    cells: &[(usize, usize)],
+    smooth: bool,
+    lang: Lang,
+) -> Vec<PathSegment> {
+    if smooth {
+        let waypoints = smooth_path(costs, cells);
+        info!("✂️ Chemin lissé : {} cases → {} points de passage", cells.len(), waypoints.len());
+        waypoint_segments(map, costs, travel, &waypoints, lang)
+    } else {
+        reconstruct_path(map, costs, travel, cells, lang)
+    }
+}
+
+/// Tournée multi-arrêts : ordre de visite et chemin de chaque étape
+pub struct Tour {
+    pub order: Vec<usize>,              // Indices des arrêts (dans la liste reçue) dans l'ordre de visite
+    pub legs: Vec<Vec<(usize, usize)>>, // Cases de chaque étape, retour au départ compris
+    pub total_cost: f32,
+}
+
+/// Calcule les chemins entre le départ et chaque arrêt deux à deux, par un Dijkstra à cibles
+/// multiples depuis chaque point, puis l'ordre de visite le moins coûteux (voir `navigation::tour`)
+pub fn plan_tour(
+    map: &Map,
+    costs: &CostGrid,
+    start: (usize, usize),
+    stops: &[(usize, usize)],
+    return_to_start: bool,
+    budget: &SearchBudget,
+) -> (Result<Tour, Message>, SearchStats) {
+    info!("🗺️ Tournée de {} arrêts depuis {:?}", stops.len(), start);
+    measure("dijkstra", budget, || tour_legs(map, costs, start, stops, return_to_start))
+}
+
+fn tour_legs(
+    map: &Map,
+    costs: &CostGrid,
+    start: (usize, usize),
+    stops: &[(usize, usize)],
+    return_to_start: bool,
+) -> Result<Tour, Message> {
+    let points: Vec<(usize, usize)> = std::iter::once(start).chain(stops.iter().copied()).collect();
+    let mut paths = HashMap::new(); // (i, j) -> cases du chemin de i à j
+    let mut cost = vec![vec![f32::INFINITY; points.len()]; points.len()];
+
+    // Les chemins depuis le départ d'abord : un arrêt inaccessible arrête tout de suite le calcul
+    for (i, &from) in points.iter().enumerate() {
+        if i == 1 && cost[0][1..].iter().any(|c| !c.is_finite()) {
+            break;
+        }
+        // Sans retour, aucune étape ne termine sur le départ
+        let targets: Vec<usize> = (0..points.len()).filter(|&j| j != i && (j != 0 || return_to_start)).collect();
+        let cells: Vec<(usize, usize)> = targets.iter().map(|&j| points[j]).collect();
+
+        for (j, found) in targets.into_iter().zip(paths_to_targets(map, costs, from, &cells)) {
+            if let Some(found) = found {
+                cost[i][j] = path_cost(costs, &found);
+                paths.insert((i, j), found);
+            }
+        }
+    }
+
+    match (1..points.len()).find(|&k| !cost[0][k].is_finite()) {
+        Some(k) => Err(Message::UnreachableStop(points[k])),
+        None => visiting_order(&cost, return_to_start).ok_or(Message::NoPathFound).and_then(|order| {
+            let mut visits = vec![0];
+            visits.extend(&order);
+            if return_to_start {
+                visits.push(0);
+            }
+
+            // Une étape sans chemin donnerait une tournée incomplète : on échoue plutôt que de la sauter
+            let legs = visits
+                .windows(2)
+                .map(|pair| paths.remove(&(pair[0], pair[1])).ok_or(Message::NoPathFound))
+                .collect::<Result<Vec<_>, _>>()?;
+            let total_cost = visits.windows(2).map(|pair| cost[pair[0]][pair[1]]).sum();
+            Ok(Tour { order: order.iter().map(|stop| stop - 1).collect(), legs, total_cost })
+        }),
+    }
+}
@@ -0,0 +1,277 @@
+// Cache des itinéraires calculés par `/navigate`.
+//
+// La plupart des requêtes répètent les mêmes trajets (entrée principale vers les salles les plus
+// demandées...). La clé contient la version des données d'itinéraire : dès qu'une fermeture ou
+// un danger change les coûts, les anciennes entrées ne peuvent plus servir et le cache est vidé.
+// Cette version est commune à tous les profils, recalculés ensemble à chaque modification : le
+// cache est donc vidé pour tous les profils à la fois, y compris ceux dont les coûts n'ont pas
+// changé (une pénalité limitée à un profil n'existe pas).
+// Seul le chemin est mis en cache : instructions, lissage et langue sont recalculés à chaque fois.
+//
+// L'ordre d'utilisation est une liste doublement chaînée rangée dans un vecteur, indexée par une
+// table de hachage : lecture, insertion et éviction se font en temps constant.
+
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Mutex, MutexGuard};
+use crate::services::pathfinding::SearchStats;
+use log::info;
+use serde::Serialize;
+
+/// Départ ou arrivée tel que demandé : une case précise ou une salle
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Place {
+    Cell((usize, usize)),
+    Room(usize),
+}
+
+/// Clé d'un itinéraire : version des coûts, profil, algorithme, départ et arrivée
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RouteKey {
+    pub version: u64,
+    pub profile: String,
+    pub algorithm: &'static str,
+    pub start: Place,
+    pub end: Place,
+}
+
+/// Résultat d'une recherche terminée (les recherches interrompues ne sont pas mises en cache)
+pub type CachedRoute = (Option<Vec<(usize, usize)>>, SearchStats);
+
+/// Compteurs du cache, exposés par `/health`
+#[derive(Debug, Serialize)]
+pub struct CacheMetrics {
+    pub enabled: bool,
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+    pub capacity: usize,
+}
+
+// Indice réservé pour "aucune entrée" dans la liste chaînée
+const NIL: usize = usize::MAX;
+
+// Entrée du cache, maillon de la liste des entrées par utilisation décroissante
+struct Slot {
+    key: RouteKey,
+    route: CachedRoute,
+    previous: usize, // Entrée utilisée plus récemment
+    next: usize,     // Entrée utilisée moins récemment
+}
+
+// Entrées et version des coûts qu'elles concernent
+struct Entries {
+    version: u64,
+    index: HashMap<RouteKey, usize>, // Place de chaque clé dans `slots`
+    slots: Vec<Slot>,
+    newest: usize, // Entrée la plus récemment utilisée
+    oldest: usize, // Entrée la moins récemment utilisée, la prochaine évincée
+}
+
+impl Entries {
+    fn new() -> Self {
+        Self { version: 0, index: HashMap::new(), slots: Vec::new(), newest: NIL, oldest: NIL }
+    }
+
+    fn len(&self) -> usize {
+        self.slots.len()
+    }
+
+    fn clear(&mut self) {
+        self.index.clear();
+        self.slots.clear();
+        (self.newest, self.oldest) = (NIL, NIL);
+    }
+
+    // Retire une entrée de la liste (elle reste dans `slots`)
+    fn unlink(&mut self, slot: usize) {
+        let (previous, next) = (self.slots[slot].previous, self.slots[slot].next);
+        match previous {
+            NIL => self.newest = next,
+            previous => self.slots[previous].next = next,
+        }
+        match next {
+            NIL => self.oldest = previous,
+            next => self.slots[next].previous = previous,
+        }
+    }
+
+    // Place une entrée en tête de liste, comme la plus récemment utilisée
+    fn push_newest(&mut self, slot: usize) {
+        self.slots[slot].previous = NIL;
+        self.slots[slot].next = self.newest;
+        match self.newest {
+            NIL => self.oldest = slot,
+            newest => self.slots[newest].previous = slot,
+        }
+        self.newest = slot;
+    }
+
+    fn get(&mut self, key: &RouteKey) -> Option<&CachedRoute> {
+        let slot = *self.index.get(key)?;
+        self.unlink(slot);
+        self.push_newest(slot);
+        Some(&self.slots[slot].route)
+    }
+
+    // Enregistre une entrée ; plein, le cache réutilise la place de la moins récemment utilisée
+    fn insert(&mut self, key: RouteKey, route: CachedRoute, capacity: usize) {
+        if let Some(&slot) = self.index.get(&key) {
+            self.slots[slot].route = route;
+            self.unlink(slot);
+            self.push_newest(slot);
+            return;
+        }
+
+        let entry = Slot { key: key.clone(), route, previous: NIL, next: NIL };
+        let slot = if self.slots.len() < capacity {
+            self.slots.push(entry);
+            self.slots.len() - 1
+        } else {
+            let slot = self.oldest;
+            self.unlink(slot);
+            let evicted = std::mem::replace(&mut self.slots[slot], entry);
+            self.index.remove(&evicted.key);
+            slot
+        };
+        self.index.insert(key, slot);
+        self.push_newest(slot);
+    }
+}
+
+/// Cache LRU des itinéraires, désactivable (CACHE_ENABLED=false)
+pub struct RouteCache {
+    enabled: bool,
+    capacity: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl RouteCache {
+    pub fn new(enabled: bool, capacity: usize) -> Self {
+        let enabled = enabled && capacity > 0;
+        info!("🗃️ Cache des itinéraires : {}", if enabled { format!("{} entrées", capacity) } else { "désactivé".to_string() });
+        Self {
+            enabled,
+            capacity,
+            entries: Mutex::new(Entries::new()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    /// Itinéraire en cache pour cette clé ; ses mesures sont celles de la recherche d'origine
+    pub fn get(&self, key: &RouteKey) -> Option<CachedRoute> {
+        if !self.enabled {
+            return None;
+        }
+
+        let mut entries = self.lock(key.version);
+        let found = entries.get(key).map(|route| {
+            let (cells, stats) = route.clone();
+            (cells, SearchStats { cached: true, ..stats })
+        });
+
+        let counter = if found.is_some() { &self.hits } else { &self.misses };
+        counter.fetch_add(1, Ordering::Relaxed);
+        found
+    }
+
+    /// Enregistre un itinéraire ; évince l'entrée la moins récemment utilisée si le cache est plein
+    pub fn insert(&self, key: RouteKey, route: CachedRoute) {
+        if !self.enabled || route.1.aborted.is_some() {
+            return;
+        }
+
+        let mut entries = self.lock(key.version);
+        if key.version < entries.version {
+            return; // Calculé sur des coûts déjà remplacés
+        }
+        entries.insert(key, route, self.capacity);
+    }
+
+    pub fn metrics(&self) -> CacheMetrics {
+        CacheMetrics {
+            enabled: self.enabled,
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap_or_else(|e| e.into_inner()).len(),
+            capacity: self.capacity,
+        }
+    }
+
+    // Verrouille les entrées en vidant celles d'une version antérieure à `version`
+    fn lock(&self, version: u64) -> MutexGuard<'_, Entries> {
+        let mut entries = self.entries.lock().unwrap_or_else(|e| e.into_inner());
+        if version > entries.version {
+            if entries.len() > 0 {
+                info!("🗑️ Cache des itinéraires vidé : {} entrées de la version {} périmées", entries.len(), entries.version);
+            }
+            entries.clear();
+            entries.version = version;
+        }
+        entries
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn key(version: u64, room: usize) -> RouteKey {
+        RouteKey { version, profile: "default".to_string(), algorithm: "astar", start: Place::Cell((0, 0)), end: Place::Room(room) }
+    }
+
+    fn route(length: usize) -> CachedRoute {
+        let stats = SearchStats { algorithm: "astar", expanded_nodes: 0, duration_ms: 0.0, aborted: None, cached: false, max_extra_cost: None };
+        (Some(vec![(0, 0); length]), stats)
+    }
+
+    #[test]
+    fn evicts_the_least_recently_used_route() {
+        let cache = RouteCache::new(true, 3);
+        for room in 1..=3 {
+            cache.insert(key(1, room), route(room));
+        }
+        // La salle 1 redevient la plus récente : la salle 2 part la première, puis la salle 3
+        assert!(cache.get(&key(1, 1)).is_some());
+        cache.insert(key(1, 4), route(4));
+        assert!(cache.get(&key(1, 2)).is_none());
+        cache.insert(key(1, 5), route(5));
+        assert!(cache.get(&key(1, 3)).is_none());
+
+        for room in [1, 4, 5] {
+            let (cells, stats) = cache.get(&key(1, room)).expect("itinéraire évincé à tort");
+            assert_eq!(cells.map(|cells| cells.len()), Some(room));
+            assert!(stats.cached);
+        }
+        assert_eq!(cache.metrics().entries, 3);
+    }
+
+    #[test]
+    fn replacing_a_route_keeps_one_entry() {
+        let cache = RouteCache::new(true, 2);
+        cache.insert(key(1, 1), route(1));
+        cache.insert(key(1, 2), route(2));
+        cache.insert(key(1, 1), route(7));
+        cache.insert(key(1, 3), route(3));
+
+        assert_eq!(cache.get(&key(1, 1)).and_then(|(cells, _)| cells).map(|cells| cells.len()), Some(7));
+        assert!(cache.get(&key(1, 2)).is_none());
+        assert_eq!(cache.metrics().entries, 2);
+    }
+
+    #[test]
+    fn new_version_clears_every_profile() {
+        let cache = RouteCache::new(true, 4);
+        cache.insert(key(1, 1), route(1));
+        cache.insert(RouteKey { profile: "wheelchair".to_string(), ..key(1, 2) }, route(2));
+
+        assert!(cache.get(&key(2, 3)).is_none());
+        assert_eq!(cache.metrics().entries, 0);
+        // Un itinéraire calculé sur les anciens coûts n'est plus accepté
+        cache.insert(key(1, 1), route(1));
+        assert_eq!(cache.metrics().entries, 0);
+    }
+}
@@ -0,0 +1,157 @@
+// Heure locale du bâtiment, en secondes depuis le 1er janvier 1970 à 00:00 heure locale.
+//
+// Les horaires d'ouverture s'expriment en heure locale : on décale l'heure système
+// d'un nombre fixe de minutes (UTC_OFFSET_MINUTES). Ce décalage ne suit pas l'heure d'été :
+// il faut le changer (et redémarrer le service) aux changements d'heure, sans quoi les
+// horaires sont appliqués avec une heure d'écart. Une horloge figée (FIXED_CLOCK)
+// permet de rejouer un calendrier à une date donnée, pour les tests et les démonstrations.
+
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Source de l'heure locale courante
+pub trait Clock: Send + Sync {
+    fn now(&self) -> u64;
+}
+
+/// Horloge système décalée vers l'heure locale
+pub struct SystemClock {
+    pub utc_offset_minutes: i64,
+}
+
+impl Clock for SystemClock {
+    fn now(&self) -> u64 {
+        let utc = SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |elapsed| elapsed.as_secs());
+        utc.saturating_add_signed(self.utc_offset_minutes * 60)
+    }
+}
+
+/// Horloge arrêtée sur un instant donné
+pub struct FixedClock(pub u64);
+
+impl Clock for FixedClock {
+    fn now(&self) -> u64 {
+        self.0
+    }
+}
+
+// Nombre de jours depuis le 1er janvier 1970 (algorithme de H. Hinnant)
+fn days_from_civil(year: i64, month: u32, day: u32) -> i64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let month = month as i64;
+    let day_of_year = (153 * (if month > 2 { month - 3 } else { month + 9 }) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    era * 146097 + day_of_era - 719468
+}
+
+// Date (année, mois, jour) d'un nombre de jours depuis le 1er janvier 1970
+fn civil_from_days(days: i64) -> (i64, u32, u32) {
+    let days = days + 719468;
+    let era = days.div_euclid(146097);
+    let day_of_era = days - era * 146097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36524 - day_of_era / 146096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let mp = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * mp + 2) / 5 + 1) as u32;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 } as u32;
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    (year, month, day)
+}
+
+// Nombre de jours du mois (années bissextiles du calendrier grégorien)
+fn days_in_month(year: i64, month: u32) -> u32 {
+    match month {
+        2 if year % 4 == 0 && (year % 100 != 0 || year % 400 == 0) => 29,
+        2 => 28,
+        4 | 6 | 9 | 11 => 30,
+        _ => 31,
+    }
+}
+
+/// Minutes écoulées depuis minuit pour une heure `HH:MM` (`24:00` accepté pour la fin de journée)
+pub fn parse_time_of_day(value: &str) -> Option<u32> {
+    let (hours, minutes) = value.trim().split_once(':')?;
+    let (hours, minutes): (u32, u32) = (hours.parse().ok()?, minutes.parse().ok()?);
+    let total = hours * 60 + minutes;
+    (minutes < 60 && total <= 24 * 60).then_some(total)
+}
+
+/// Instant local d'une date `AAAA-MM-JJTHH:MM[:SS]`, ou d'une heure `HH:MM` le jour de `today`
+pub fn parse_datetime(value: &str, today: u64) -> Option<u64> {
+    let Some((date, time)) = value.split_once(['T', ' ']) else {
+        let minutes = parse_time_of_day(value)?;
+        return Some(today - today % 86400 + minutes as u64 * 60);
+    };
+
+    let mut parts = date.split('-');
+    let year: i64 = parts.next()?.parse().ok()?;
+    let month: u32 = parts.next()?.parse().ok()?;
+    let day: u32 = parts.next()?.parse().ok()?;
+    if parts.next().is_some() || !(1..=12).contains(&month) || !(1..=days_in_month(year, month)).contains(&day) {
+        return None;
+    }
+
+    let (time, seconds) = match time.rsplit_once(':').filter(|(head, _)| head.contains(':')) {
+        Some((time, seconds)) => (time, seconds.parse::<u64>().ok().filter(|s| *s < 60)?),
+        None => (time, 0),
+    };
+    let minutes = parse_time_of_day(time)?;
+
+    let days = u64::try_from(days_from_civil(year, month, day)).ok()?;
+    Some(days * 86400 + minutes as u64 * 60 + seconds)
+}
+
+/// Date locale au format `AAAA-MM-JJTHH:MM:SS`
+pub fn format_datetime(instant: u64) -> String {
+    let (year, month, day) = civil_from_days((instant / 86400) as i64);
+    let seconds = instant % 86400;
+    format!("{:04}-{:02}-{:02}T{:02}:{:02}:{:02}", year, month, day, seconds / 3600, seconds / 60 % 60, seconds % 60)
+}
+
+/// Jour de la semaine (0 = lundi, 6 = dimanche) ; le 1er janvier 1970 était un jeudi
+pub fn weekday(instant: u64) -> usize {
+    ((instant / 86400 + 3) % 7) as usize
+}
+
+/// Minutes écoulées depuis minuit
+pub fn minute_of_day(instant: u64) -> u32 {
+    (instant % 86400 / 60) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // Lundi 14 octobre 2024, 10:30 heure locale
+    const MONDAY: u64 = 1_728_901_800;
+
+    #[test]
+    fn dates_round_trip() {
+        assert_eq!(format_datetime(MONDAY), "2024-10-14T10:30:00");
+        assert_eq!(parse_datetime("2024-10-14T10:30", 0), Some(MONDAY));
+        assert_eq!(parse_datetime("2024-10-14 10:30:15", 0), Some(MONDAY + 15));
+        assert_eq!(weekday(MONDAY), 0);
+        assert_eq!(minute_of_day(MONDAY), 10 * 60 + 30);
+    }
+
+    #[test]
+    fn time_of_day_uses_the_clock_date() {
+        let clock = FixedClock(MONDAY);
+        assert_eq!(parse_datetime("08:15", clock.now()).map(format_datetime).as_deref(), Some("2024-10-14T08:15:00"));
+        assert_eq!(parse_datetime("24:00", clock.now()).map(format_datetime).as_deref(), Some("2024-10-15T00:00:00"));
+        assert_eq!(parse_datetime("10:60", clock.now()), None);
+    }
+
+    #[test]
+    fn days_are_checked_against_the_month() {
+        assert!(parse_datetime("2024-02-29T12:00", 0).is_some());
+        assert!(parse_datetime("2000-02-29T12:00", 0).is_some());
+        assert_eq!(parse_datetime("2023-02-29T12:00", 0), None);
+        assert_eq!(parse_datetime("1900-02-29T12:00", 0), None);
+        assert_eq!(parse_datetime("2024-02-31T12:00", 0), None);
+        assert_eq!(parse_datetime("2024-04-31T12:00", 0), None);
+        assert!(parse_datetime("2024-12-31T12:00", 0).is_some());
+        assert_eq!(parse_datetime("2024-13-01T12:00", 0), None);
+    }
+}
@@ -0,0 +1,297 @@
+use crate::data::loader::SurfaceType;
+use crate::navigation::budget::AbortReason;
+
+/// Langues des messages de l'API, des noms de surfaces et des instructions (français par défaut)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Lang {
+    #[default]
+    Fr,
+    En,
+}
+
+/// Messages renvoyés par l'API
+#[derive(Debug, Clone, PartialEq)]
+pub enum Message {
+    PathFound,
+    NoPathFound,
+    UnknownProfile(String),
+    UnknownAlgorithm(String),
+    LandmarksUnavailable,
+    TourFound,
+    NoStops,
+    TooManyStops(usize),
+    InvalidStops(String),
+    UnknownRoom(usize),
+    UnreachableStop((usize, usize)),
+    UnknownFacility(String),
+    NoFacilityFound,
+    MissingEndpoint,
+    ClosureCreated,
+    ClosureDeleted,
+    ClosureNotFound(u64),
+    EmptyClosureArea,
+    InvalidPenalty,
+    ExpiryInPast,
+    InvalidDuration,
+    InvalidDepartureTime(String),
+    IsochroneFound,
+    MissingOrigin,
+    OutsideMap((usize, usize)),
+    InvalidBudget,
+    UnknownIsochroneFormat(String),
+    EvacuationReady,
+    HazardDeclared,
+    HazardRemoved,
+    HazardNotFound(u64),
+    SearchAborted(AbortReason),
+    ServerBusy,
+    SearchCrashed,
+    DistancesReady,
+    InvalidRoomList(String),
+    // Instructions de guidage (voir `navigation::instructions`)
+    StepDepart(String),
+    StepStraight(f32), // Mètres, arrondis à l'affichage
+    StepSlightLeft,
+    StepSlightRight,
+    StepLeft,
+    StepRight,
+    StepUTurn,
+    StepEnterRoom(String),
+    StepEnterCorridor,
+    StepGoOutside,
+    StepTakeStairs,
+    StepTakeElevator,
+    StepArrive(String),
+}
+
+impl Lang {
+    /// Langue correspondant à une étiquette comme `fr`, `en-US` ou `EN`
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let primary = tag.trim().split(['-', '_']).next()?.to_ascii_lowercase();
+        match primary.as_str() {
+            "fr" => Some(Lang::Fr),
+            "en" => Some(Lang::En),
+            _ => None,
+        }
+    }
+
+    /// Choisit la langue : paramètre `lang` s'il est reconnu, sinon la langue préférée
+    /// de l'en-tête `Accept-Language` (selon les poids `q`), sinon le français
+    pub fn negotiate(param: Option<&str>, accept_language: Option<&str>) -> Self {
+        if let Some(lang) = param.and_then(Lang::from_tag) {
+            return lang;
+        }
+
+        let mut candidates: Vec<(Lang, f32)> = accept_language
+            .unwrap_or_default()
+            .split(',')
+            .filter_map(|entry| {
+                let mut parts = entry.split(';');
+                let lang = Lang::from_tag(parts.next()?)?;
+                let quality = parts
+                    .find_map(|p| p.trim().strip_prefix("q="))
+                    .and_then(|q| q.parse().ok())
+                    .unwrap_or(1.0);
+                Some((lang, quality))
+            })
+            .filter(|(_, quality)| *quality > 0.0)
+            .collect();
+
+        // Tri stable : à poids égal, l'ordre de l'en-tête est conservé
+        candidates.sort_by(|a, b| b.1.total_cmp(&a.1));
+        candidates.first().map_or_else(Lang::default, |(lang, _)| *lang)
+    }
+
+    /// Code de langue, pour l'en-tête `Content-Language`
+    pub fn code(self) -> &'static str {
+        match self {
+            Lang::Fr => "fr",
+            Lang::En => "en",
+        }
+    }
+
+    pub fn message(self, message: &Message) -> String {
+        match (self, message) {
+            (Lang::Fr, Message::PathFound) => "Chemin trouvé avec succès".to_string(),
+            (Lang::En, Message::PathFound) => "Path found successfully".to_string(),
+            (Lang::Fr, Message::NoPathFound) => "Aucun chemin trouvé.".to_string(),
+            (Lang::En, Message::NoPathFound) => "No path found.".to_string(),
+            (Lang::Fr, Message::UnknownProfile(name)) => format!("Profil d'itinéraire inconnu : `{}`", name),
+            (Lang::En, Message::UnknownProfile(name)) => format!("Unknown routing profile: `{}`", name),
+            (Lang::Fr, Message::UnknownAlgorithm(name)) => format!("Algorithme inconnu : `{}`", name),
+            (Lang::En, Message::UnknownAlgorithm(name)) => format!("Unknown algorithm: `{}`", name),
+            (Lang::Fr, Message::LandmarksUnavailable) => "Les repères ALT ne sont pas précalculés (LANDMARK_COUNT=0).".to_string(),
+            (Lang::En, Message::LandmarksUnavailable) => "ALT landmarks are not precomputed (LANDMARK_COUNT=0).".to_string(),
+            (Lang::Fr, Message::TourFound) => "Tournée calculée avec succès".to_string(),
+            (Lang::En, Message::TourFound) => "Tour planned successfully".to_string(),
+            (Lang::Fr, Message::NoStops) => "Aucun arrêt à visiter (paramètres `stops` ou `rooms`).".to_string(),
+            (Lang::En, Message::NoStops) => "No stops to visit (`stops` or `rooms` parameters).".to_string(),
+            (Lang::Fr, Message::TooManyStops(max)) => format!("Trop d'arrêts : {} au maximum.", max),
+            (Lang::En, Message::TooManyStops(max)) => format!("Too many stops: at most {}.", max),
+            (Lang::Fr, Message::InvalidStops(value)) => format!("Liste d'arrêts invalide : `{}`", value),
+            (Lang::En, Message::InvalidStops(value)) => format!("Invalid stop list: `{}`", value),
+            (Lang::Fr, Message::UnknownRoom(room)) => format!("Salle inconnue : {}", room),
+            (Lang::En, Message::UnknownRoom(room)) => format!("Unknown room: {}", room),
+            (Lang::Fr, Message::UnreachableStop((x, y))) => format!("Arrêt inaccessible depuis le départ : ({}, {})", x, y),
+            (Lang::En, Message::UnreachableStop((x, y))) => format!("Stop unreachable from the start: ({}, {})", x, y),
+            (Lang::Fr, Message::UnknownFacility(name)) => {
+                format!("Type de lieu inconnu : `{}` (couloir, escalier, ascenseur, exterieur, salle, sortie)", name)
+            }
+            (Lang::En, Message::UnknownFacility(name)) => {
+                format!("Unknown place type: `{}` (corridor, stairs, elevator, outdoors, room, exit)", name)
+            }
+            (Lang::Fr, Message::NoFacilityFound) => "Aucun lieu de ce type n'est accessible.".to_string(),
+            (Lang::En, Message::NoFacilityFound) => "No place of this type is reachable.".to_string(),
+            (Lang::Fr, Message::MissingEndpoint) => {
+                "Départ ou arrivée manquant : indiquez start_x/start_y ou from_room, et end_x/end_y ou to_room.".to_string()
+            }
+            (Lang::En, Message::MissingEndpoint) => {
+                "Missing start or end: give start_x/start_y or from_room, and end_x/end_y or to_room.".to_string()
+            }
+            (Lang::Fr, Message::ClosureCreated) => "Fermeture enregistrée, itinéraires mis à jour dans un instant".to_string(),
+            (Lang::En, Message::ClosureCreated) => "Closure registered, routes will be updated shortly".to_string(),
+            (Lang::Fr, Message::ClosureDeleted) => "Fermeture supprimée, itinéraires mis à jour dans un instant".to_string(),
+            (Lang::En, Message::ClosureDeleted) => "Closure deleted, routes will be updated shortly".to_string(),
+            (Lang::Fr, Message::ClosureNotFound(id)) => format!("Fermeture inconnue : {}", id),
+            (Lang::En, Message::ClosureNotFound(id)) => format!("Unknown closure: {}", id),
+            (Lang::Fr, Message::EmptyClosureArea) => "La zone fermée ne couvre aucune case de la carte.".to_string(),
+            (Lang::En, Message::EmptyClosureArea) => "The closed area does not cover any cell of the map.".to_string(),
+            (Lang::Fr, Message::InvalidPenalty) => "La pénalité doit être un multiplicateur supérieur ou égal à 1.".to_string(),
+            (Lang::En, Message::InvalidPenalty) => "The penalty must be a multiplier greater than or equal to 1.".to_string(),
+            (Lang::Fr, Message::ExpiryInPast) => "La date d'expiration est déjà passée.".to_string(),
+            (Lang::En, Message::ExpiryInPast) => "The expiry date is already in the past.".to_string(),
+            (Lang::Fr, Message::InvalidDuration) => "Durée trop longue : la date d'expiration dépasse les dates représentables.".to_string(),
+            (Lang::En, Message::InvalidDuration) => "Duration too long: the expiry date is out of range.".to_string(),
+            (Lang::Fr, Message::InvalidDepartureTime(value)) => {
+                format!("Heure de départ invalide : `{}` (format attendu : AAAA-MM-JJTHH:MM ou HH:MM)", value)
+            }
+            (Lang::En, Message::InvalidDepartureTime(value)) => {
+                format!("Invalid departure time: `{}` (expected format: YYYY-MM-DDTHH:MM or HH:MM)", value)
+            }
+            (Lang::Fr, Message::IsochroneFound) => "Zone accessible calculée".to_string(),
+            (Lang::En, Message::IsochroneFound) => "Reachable area computed".to_string(),
+            (Lang::Fr, Message::MissingOrigin) => "Point de départ manquant : indiquez start_x/start_y, from_room ou from_type.".to_string(),
+            (Lang::En, Message::MissingOrigin) => "Missing origin: give start_x/start_y, from_room or from_type.".to_string(),
+            (Lang::Fr, Message::OutsideMap((x, y))) => format!("Position hors de la carte : ({}, {})", x, y),
+            (Lang::En, Message::OutsideMap((x, y))) => format!("Position outside the map: ({}, {})", x, y),
+            (Lang::Fr, Message::InvalidBudget) => {
+                "Budget invalide : indiquez soit max_distance (mètres), soit max_time (secondes), strictement positif.".to_string()
+            }
+            (Lang::En, Message::InvalidBudget) => {
+                "Invalid budget: give either max_distance (metres) or max_time (seconds), strictly positive.".to_string()
+            }
+            (Lang::Fr, Message::UnknownIsochroneFormat(name)) => format!("Format inconnu : `{}` (masque, polygone, salles)", name),
+            (Lang::En, Message::UnknownIsochroneFormat(name)) => format!("Unknown format: `{}` (mask, polygon, rooms)", name),
+            (Lang::Fr, Message::EvacuationReady) => "Champ d'évacuation à jour".to_string(),
+            (Lang::En, Message::EvacuationReady) => "Evacuation field up to date".to_string(),
+            (Lang::Fr, Message::HazardDeclared) => "Zone dangereuse déclarée, champs d'évacuation mis à jour dans un instant".to_string(),
+            (Lang::En, Message::HazardDeclared) => "Hazard declared, evacuation fields will be updated shortly".to_string(),
+            (Lang::Fr, Message::HazardRemoved) => "Zone dangereuse levée, champs d'évacuation mis à jour dans un instant".to_string(),
+            (Lang::En, Message::HazardRemoved) => "Hazard cleared, evacuation fields will be updated shortly".to_string(),
+            (Lang::Fr, Message::HazardNotFound(id)) => format!("Zone dangereuse inconnue : {}", id),
+            (Lang::En, Message::HazardNotFound(id)) => format!("Unknown hazard: {}", id),
+            (Lang::Fr, Message::SearchAborted(AbortReason::NodeLimit)) => {
+                "Recherche interrompue : trop de cases explorées. Réessayez avec un trajet plus court.".to_string()
+            }
+            (Lang::En, Message::SearchAborted(AbortReason::NodeLimit)) => {
+                "Search aborted: too many cells explored. Try a shorter route.".to_string()
+            }
+            (Lang::Fr, Message::SearchAborted(AbortReason::Timeout)) => "Recherche interrompue : délai dépassé.".to_string(),
+            (Lang::En, Message::SearchAborted(AbortReason::Timeout)) => "Search aborted: time limit exceeded.".to_string(),
+            (Lang::Fr, Message::SearchAborted(AbortReason::Cancelled)) => "Recherche annulée.".to_string(),
+            (Lang::En, Message::SearchAborted(AbortReason::Cancelled)) => "Search cancelled.".to_string(),
+            (Lang::Fr, Message::ServerBusy) => "Serveur surchargé, réessayez dans un instant.".to_string(),
+            (Lang::En, Message::ServerBusy) => "Server busy, please retry shortly.".to_string(),
+            (Lang::Fr, Message::SearchCrashed) => "Erreur interne pendant la recherche.".to_string(),
+            (Lang::En, Message::SearchCrashed) => "Internal error during the search.".to_string(),
+            (Lang::Fr, Message::DistancesReady) => "Distances entre salles".to_string(),
+            (Lang::En, Message::DistancesReady) => "Distances between rooms".to_string(),
+            (Lang::Fr, Message::InvalidRoomList(value)) => format!("Liste de salles invalide : `{}` (attendu : \"3,8,...\")", value),
+            (Lang::En, Message::InvalidRoomList(value)) => format!("Invalid room list: `{}` (expected: \"3,8,...\")", value),
+            (Lang::Fr, Message::StepDepart(name)) => format!("Départ : {}", name),
+            (Lang::En, Message::StepDepart(name)) => format!("Start: {}", name),
+            (Lang::Fr, Message::StepStraight(meters)) => format!("Continuez tout droit sur {} m", meters.round()),
+            (Lang::En, Message::StepStraight(meters)) => format!("Go straight for {} m", meters.round()),
+            (Lang::Fr, Message::StepSlightLeft) => "Tournez légèrement à gauche".to_string(),
+            (Lang::En, Message::StepSlightLeft) => "Turn slightly left".to_string(),
+            (Lang::Fr, Message::StepSlightRight) => "Tournez légèrement à droite".to_string(),
+            (Lang::En, Message::StepSlightRight) => "Turn slightly right".to_string(),
+            (Lang::Fr, Message::StepLeft) => "Tournez à gauche".to_string(),
+            (Lang::En, Message::StepLeft) => "Turn left".to_string(),
+            (Lang::Fr, Message::StepRight) => "Tournez à droite".to_string(),
+            (Lang::En, Message::StepRight) => "Turn right".to_string(),
+            (Lang::Fr, Message::StepUTurn) => "Faites demi-tour".to_string(),
+            (Lang::En, Message::StepUTurn) => "Make a U-turn".to_string(),
+            (Lang::Fr, Message::StepEnterRoom(name)) => format!("Entrez dans {}", name),
+            (Lang::En, Message::StepEnterRoom(name)) => format!("Enter {}", name),
+            (Lang::Fr, Message::StepEnterCorridor) => "Entrez dans le couloir".to_string(),
+            (Lang::En, Message::StepEnterCorridor) => "Enter the corridor".to_string(),
+            (Lang::Fr, Message::StepGoOutside) => "Sortez à l'extérieur".to_string(),
+            (Lang::En, Message::StepGoOutside) => "Go outside".to_string(),
+            (Lang::Fr, Message::StepTakeStairs) => "Prenez l'escalier".to_string(),
+            (Lang::En, Message::StepTakeStairs) => "Take the stairs".to_string(),
+            (Lang::Fr, Message::StepTakeElevator) => "Prenez l'ascenseur".to_string(),
+            (Lang::En, Message::StepTakeElevator) => "Take the elevator".to_string(),
+            (Lang::Fr, Message::StepArrive(name)) => format!("Vous êtes arrivé : {}", name),
+            (Lang::En, Message::StepArrive(name)) => format!("You have arrived: {}", name),
+        }
+    }
+
+    /// Nom d'une surface, tel qu'affiché dans les segments et les instructions
+    pub fn surface_name(self, surface: SurfaceType) -> String {
+        match (self, surface) {
+            (Lang::Fr, SurfaceType::Mur) => "Mur".to_string(),
+            (Lang::Fr, SurfaceType::Couloir) => "Couloir".to_string(),
+            (Lang::Fr, SurfaceType::Exterieur) => "Exterieur".to_string(),
+            (Lang::Fr, SurfaceType::Escalier) => "Escalier".to_string(),
+            (Lang::Fr, SurfaceType::Ascenseur) => "Ascenseur".to_string(),
+            (Lang::Fr, SurfaceType::Room(id)) => format!("Salle {}", id),
+            (Lang::En, SurfaceType::Mur) => "Wall".to_string(),
+            (Lang::En, SurfaceType::Couloir) => "Corridor".to_string(),
+            (Lang::En, SurfaceType::Exterieur) => "Outdoors".to_string(),
+            (Lang::En, SurfaceType::Escalier) => "Stairs".to_string(),
+            (Lang::En, SurfaceType::Ascenseur) => "Elevator".to_string(),
+            (Lang::En, SurfaceType::Room(id)) => format!("Room {}", id),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn tags_are_matched_on_their_primary_subtag() {
+        assert_eq!(Lang::from_tag("fr"), Some(Lang::Fr));
+        assert_eq!(Lang::from_tag(" EN-us"), Some(Lang::En));
+        assert_eq!(Lang::from_tag("en_GB"), Some(Lang::En));
+        assert_eq!(Lang::from_tag("de"), None);
+        assert_eq!(Lang::from_tag(""), None);
+    }
+
+    #[test]
+    fn lang_parameter_overrides_the_header() {
+        assert_eq!(Lang::negotiate(Some("en"), Some("fr-FR,fr;q=0.9")), Lang::En);
+        assert_eq!(Lang::negotiate(Some("fr"), Some("en")), Lang::Fr);
+        // Un paramètre inconnu laisse la main à l'en-tête
+        assert_eq!(Lang::negotiate(Some("xx"), Some("en")), Lang::En);
+    }
+
+    #[test]
+    fn header_quality_weights_are_honoured() {
+        assert_eq!(Lang::negotiate(None, Some("fr;q=0.4, en;q=0.8")), Lang::En);
+        assert_eq!(Lang::negotiate(None, Some("en;q=0.5,fr")), Lang::Fr);
+        assert_eq!(Lang::negotiate(None, Some("de, en-US;q=0.7, fr;q=0.3")), Lang::En);
+        // q=0 exclut la langue ; à poids égal, l'ordre de l'en-tête l'emporte
+        assert_eq!(Lang::negotiate(None, Some("en;q=0, fr;q=0.1")), Lang::Fr);
+        assert_eq!(Lang::negotiate(None, Some("en, fr")), Lang::En);
+    }
+
+    #[test]
+    fn unknown_languages_fall_back_to_french() {
+        assert_eq!(Lang::negotiate(None, None), Lang::Fr);
+        assert_eq!(Lang::negotiate(None, Some("de-DE, es;q=0.8")), Lang::Fr);
+        assert_eq!(Lang::negotiate(Some("it"), Some("*")), Lang::Fr);
+        assert_eq!(Lang::negotiate(None, Some("en;q=0")), Lang::Fr);
+    }
+}
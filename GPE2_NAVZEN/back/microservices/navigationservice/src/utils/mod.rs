@@ -1,2 +1,4 @@
+pub mod clock;
 pub mod errors;
+pub mod i18n;
 pub mod logger;